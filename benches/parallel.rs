@@ -0,0 +1,103 @@
+//! Compares the serial bouncy balls systems with their parallel variants over dense stores, at 1k, 10k and 100k balls.
+//! Run with `cargo bench --no-default-features --features parallel --bench parallel`.
+
+use std::hint::black_box;
+use std::time::Duration;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use rusty_nature_of_code::boundary::Boundary;
+use rusty_nature_of_code::bouncy_balls::parallel::*;
+use rusty_nature_of_code::bouncy_balls::prelude::*;
+use rusty_nature_of_code::rng::SimRng;
+use rusty_nature_of_code::store::{Components, DenseStore};
+
+const SIZES: [usize; 3] = [ 1_000, 10_000, 100_000 ];
+
+/// Balls spread over a screen which grows with their number, so each region holds about as many balls at every size
+#[derive(Clone)]
+struct Balls {
+	screen_size: ( i32, i32 ),
+	columns: usize,
+	rows: usize,
+	boundary: Boundary,
+	regions: Regions,
+	vel_store: DenseStore<Vec2>,
+	pos_store: DenseStore<Vec2>,
+	atr_store: DenseStore<Attributes>,
+	mat_store: DenseStore<Material>,
+	shp_store: DenseStore<Shape>,
+}
+
+fn balls( count: usize ) -> Balls {
+	/* The default bouncy balls screen holds 100 balls in 640 by 480 */
+	let scale = ( count as f32 / 100.0 ).sqrt();
+	let screen_size = ( ( 640.0 * scale ) as i32, ( 480.0 * scale ) as i32 );
+	let ( columns, rows ) = ( ( 16.0 * scale ) as usize, ( 12.0 * scale ) as usize );
+
+	let mut rng = SimRng::new( 1 );
+	let mut entity_manager = EntityManager::new();
+	let ( mut vel_store, mut pos_store, mut atr_store, mut mat_store ) = ( DenseStore::new(), DenseStore::new(), DenseStore::new(), DenseStore::new() );
+	for _ in 0..count {
+		let entity = entity_manager.next();
+		let radius = rng.range( 5.0..15.0 );
+		pos_store.add( entity, Vec2::new( &rng.range( 0.0..screen_size.0 as f32 ), &rng.range( 0.0..screen_size.1 as f32 ) ) );
+		vel_store.add( entity, Vec2::new( &rng.range( -4.0..4.0 ), &rng.range( -4.0..4.0 ) ) );
+		atr_store.add( entity, Attributes { mass: 1.0, color: Rgba::WHITE, radius, angle: 0.0, spin: 0.0, row: 0, column: 0 } );
+		mat_store.add( entity, Material::default() );
+	}
+	let mut balls = Balls { screen_size, columns, rows, boundary: Boundary::default(), regions: regions( columns, rows ), vel_store, pos_store, atr_store, mat_store, shp_store: DenseStore::new() };
+	movement_system( balls.screen_size, balls.columns, balls.rows, &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &mut balls.regions );
+	balls
+}
+
+/* Each iteration starts from a copy of the freshly spread balls, as the systems move them and change their velocities,
+   and later iterations would otherwise measure balls which have drifted off the screen or stopped colliding */
+
+fn movement( c: &mut Criterion ) {
+	let mut group = c.benchmark_group( "movement" );
+	group.sample_size( 20 ).measurement_time( Duration::from_secs( 3 ) );
+	for count in SIZES {
+		let start = balls( count );
+		group.bench_function( BenchmarkId::new( "serial", count ), |b| b.iter_batched_ref( || start.clone(), |balls| {
+			movement_system( balls.screen_size, balls.columns, balls.rows, &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &mut balls.regions )
+		}, BatchSize::LargeInput ) );
+		group.bench_function( BenchmarkId::new( "parallel", count ), |b| b.iter_batched_ref( || start.clone(), |balls| {
+			par_movement_system( balls.screen_size, balls.columns, balls.rows, &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &mut balls.regions )
+		}, BatchSize::LargeInput ) );
+	}
+	group.finish();
+}
+
+fn boundary( c: &mut Criterion ) {
+	let mut group = c.benchmark_group( "boundary" );
+	group.sample_size( 20 ).measurement_time( Duration::from_secs( 3 ) );
+	for count in SIZES {
+		let start = balls( count );
+		group.bench_function( BenchmarkId::new( "serial", count ), |b| b.iter_batched_ref( || start.clone(), |balls| {
+			black_box( boundary_system( balls.screen_size, &balls.boundary, &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &balls.mat_store, &balls.shp_store ) )
+		}, BatchSize::LargeInput ) );
+		group.bench_function( BenchmarkId::new( "parallel", count ), |b| b.iter_batched_ref( || start.clone(), |balls| {
+			black_box( par_boundary_system( balls.screen_size, &balls.boundary, &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &balls.mat_store, &balls.shp_store ) )
+		}, BatchSize::LargeInput ) );
+	}
+	group.finish();
+}
+
+fn collision( c: &mut Criterion ) {
+	let mut group = c.benchmark_group( "collision" );
+	group.sample_size( 20 ).measurement_time( Duration::from_secs( 3 ) );
+	for count in SIZES {
+		let start = balls( count );
+		group.bench_function( BenchmarkId::new( "serial", count ), |b| b.iter_batched_ref( || start.clone(), |balls| {
+			black_box( collision_system( &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &balls.mat_store, &balls.shp_store, &mut balls.regions ).len() )
+		}, BatchSize::LargeInput ) );
+		group.bench_function( BenchmarkId::new( "parallel", count ), |b| b.iter_batched_ref( || start.clone(), |balls| {
+			black_box( par_collision_system( &mut balls.vel_store, &mut balls.pos_store, &mut balls.atr_store, &balls.mat_store, &balls.shp_store, &balls.regions ).len() )
+		}, BatchSize::LargeInput ) );
+	}
+	group.finish();
+}
+
+criterion_group!( benches, movement, boundary, collision );
+criterion_main!( benches );
@@ -0,0 +1,89 @@
+//! Compares misc_ecs's HashStore with DenseStore on the bouncy balls movement and collision systems, at 1k, 10k and 100k
+//! balls. Run with `cargo bench --no-default-features --bench stores`.
+
+use std::hint::black_box;
+use std::time::Duration;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use rusty_nature_of_code::bouncy_balls::prelude::*;
+use rusty_nature_of_code::rng::SimRng;
+use rusty_nature_of_code::store::{Components, DenseStore};
+
+const SIZES: [usize; 3] = [ 1_000, 10_000, 100_000 ];
+
+/// Balls spread over a screen which grows with their number, so each region holds about as many balls at every size
+struct Balls<V, A, M> {
+	screen_size: ( i32, i32 ),
+	columns: usize,
+	rows: usize,
+	regions: Regions,
+	vel_store: V,
+	pos_store: V,
+	atr_store: A,
+	mat_store: M,
+	/* The balls are all circles, so have no shapes whichever store the others use */
+	shp_store: DenseStore<Shape>,
+}
+
+fn balls<V: Components<Vec2> + Storage, A: Components<Attributes> + Storage, M: Components<Material> + Storage>( count: usize ) -> Balls<V, A, M> {
+	/* The default bouncy balls screen holds 100 balls in 640 by 480 */
+	let scale = ( count as f32 / 100.0 ).sqrt();
+	let screen_size = ( ( 640.0 * scale ) as i32, ( 480.0 * scale ) as i32 );
+	let ( columns, rows ) = ( ( 16.0 * scale ) as usize, ( 12.0 * scale ) as usize );
+
+	let mut rng = SimRng::new( 1 );
+	let mut entity_manager = EntityManager::new();
+	let ( mut vel_store, mut pos_store, mut atr_store, mut mat_store ) = ( V::new(), V::new(), A::new(), M::new() );
+	for _ in 0..count {
+		let entity = entity_manager.next();
+		let radius = rng.range( 5.0..15.0 );
+		pos_store.add( entity, Vec2::new( &rng.range( 0.0..screen_size.0 as f32 ), &rng.range( 0.0..screen_size.1 as f32 ) ) );
+		vel_store.add( entity, Vec2::new( &rng.range( -4.0..4.0 ), &rng.range( -4.0..4.0 ) ) );
+		atr_store.add( entity, Attributes { mass: 1.0, color: Rgba::WHITE, radius, angle: 0.0, spin: 0.0, row: 0, column: 0 } );
+		mat_store.add( entity, Material::default() );
+	}
+	let mut balls = Balls { screen_size, columns, rows, regions: regions( columns, rows ), vel_store, pos_store, atr_store, mat_store, shp_store: DenseStore::new() };
+	balls.movement();
+	balls
+}
+
+impl<V: Components<Vec2>, A: Components<Attributes>, M: Components<Material>> Balls<V, A, M> {
+	fn movement( &mut self ) {
+		movement_system( self.screen_size, self.columns, self.rows, &mut self.vel_store, &mut self.pos_store, &mut self.atr_store, &mut self.regions );
+	}
+
+	fn collision( &mut self ) -> usize {
+		collision_system( &mut self.vel_store, &mut self.pos_store, &mut self.atr_store, &self.mat_store, &self.shp_store, &mut self.regions ).len()
+	}
+}
+
+/* Each iteration starts from freshly spread balls, as the systems move them and change their velocities, and later
+   iterations would otherwise measure balls which have drifted off the screen or stopped colliding */
+
+fn movement( c: &mut Criterion ) {
+	let mut group = c.benchmark_group( "movement" );
+	group.sample_size( 20 ).measurement_time( Duration::from_secs( 3 ) );
+	for count in SIZES {
+		group.bench_function( BenchmarkId::new( "HashStore", count ), |b| b.iter_batched_ref(
+			|| balls::<HashStore<Vec2>, HashStore<Attributes>, HashStore<Material>>( count ), |hash| hash.movement(), BatchSize::LargeInput ) );
+		group.bench_function( BenchmarkId::new( "DenseStore", count ), |b| b.iter_batched_ref(
+			|| balls::<DenseStore<Vec2>, DenseStore<Attributes>, DenseStore<Material>>( count ), |dense| dense.movement(), BatchSize::LargeInput ) );
+	}
+	group.finish();
+}
+
+fn collision( c: &mut Criterion ) {
+	let mut group = c.benchmark_group( "collision" );
+	group.sample_size( 20 ).measurement_time( Duration::from_secs( 3 ) );
+	for count in SIZES {
+		group.bench_function( BenchmarkId::new( "HashStore", count ), |b| b.iter_batched_ref(
+			|| balls::<HashStore<Vec2>, HashStore<Attributes>, HashStore<Material>>( count ), |hash| black_box( hash.collision() ), BatchSize::LargeInput ) );
+		group.bench_function( BenchmarkId::new( "DenseStore", count ), |b| b.iter_batched_ref(
+			|| balls::<DenseStore<Vec2>, DenseStore<Attributes>, DenseStore<Material>>( count ), |dense| black_box( dense.collision() ), BatchSize::LargeInput ) );
+	}
+	group.finish();
+}
+
+criterion_group!( benches, movement, collision );
+criterion_main!( benches );
@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+use std::process::exit;
+use rusty_nature_of_code::canvas::RasterCanvas;
+use rusty_nature_of_code::capture::{GifRecorder, PngSequence};
+use rusty_nature_of_code::config::Config;
+use rusty_nature_of_code::profile::{draw_profile, TraceWriter};
+use rusty_nature_of_code::record::{Recorder, Recording, Replay};
+use rusty_nature_of_code::sketch::{registry, step, Context, Sketch};
+
+fn usage() -> String {
+	let mut usage = format!( "usage: capture <{}> [--steps N] [--out DIR] [--gif FILE] [--debug] [--timings] [flags]\nflags:", registry::names() );
+	for ( flag, description ) in Config::FLAGS {
+		usage += &format!( "\n  {:<16} {}", flag, description );
+	}
+	usage
+}
+
+/// Where captured frames are written
+enum Output {
+	Png( PngSequence ),
+	Gif( GifRecorder ),
+}
+
+impl Output {
+	fn write( &mut self, canvas: &RasterCanvas ) {
+		let result = match self {
+			Output::Png( frames ) => frames.write( canvas ).map( |_| () ),
+			Output::Gif( gif ) => gif.add_frame( canvas ),
+		};
+		if let Err( e ) = result {
+			eprintln!( "failed to write frame: {}", e );
+			exit( 1 );
+		}
+	}
+}
+
+fn fail( message: String ) -> ! {
+	eprintln!( "{}\n{}", message, usage() );
+	exit( 2 );
+}
+
+/// Runs the sketch for steps passes, drawing each pass with the same HUD as the window does, and the timings chart if
+/// timings is true. Records the sketch and writes its timings if the config asks for it
+fn capture( sketch: &mut dyn Sketch, mut ctx: Context, steps: usize, timings: bool, canvas: &mut RasterCanvas, output: &mut Output ) {
+	println!( "seed = {}", ctx.rng.seed() );
+	sketch.setup( &mut ctx );
+	let mut recorder = Recorder::from_config( sketch, &ctx ).unwrap_or_else( |e| {
+		eprintln!( "failed to start recording: {}", e );
+		exit( 1 );
+	});
+	let mut trace = ctx.config.profile.as_ref().map( |path| TraceWriter::create( path ).unwrap_or_else( |e| {
+		eprintln!( "failed to create {}: {}", path.display(), e );
+		exit( 1 );
+	}));
+
+	for _ in 0..steps {
+		if let Some( recorder ) = recorder.as_mut() {
+			recorder.record( sketch, &ctx ).unwrap_or_else( |e| {
+				eprintln!( "failed to record: {}", e );
+				exit( 1 );
+			});
+		}
+		let pass = ctx.frame;
+		step( sketch, &mut ctx, canvas, true );
+		if timings {
+			draw_profile( canvas, &ctx.profiler );
+		}
+		output.write( canvas );
+
+		let events = ctx.profiler.end_pass();
+		if let Some( Err( e ) ) = trace.as_mut().map( |trace| trace.write( pass, &events ) ) {
+			eprintln!( "failed to write timings: {}", e );
+			exit( 1 );
+		}
+	}
+	if let Some( Err( e ) ) = trace.map( |trace| trace.finish() ) {
+		eprintln!( "failed to finish timings: {}", e );
+		exit( 1 );
+	}
+
+	if let Some( Err( e ) ) = recorder.map( |recorder| recorder.finish() ) {
+		eprintln!( "failed to finish recording: {}", e );
+		exit( 1 );
+	}
+}
+
+fn main() {
+
+	let mut args = std::env::args().skip(1);
+	let name = args.next().unwrap_or_default();
+	let mut sketch = match registry::find( &name ) {
+		Some( registration ) => ( registration.create )(),
+		None => fail( format!( "unknown sketch {:?}", name ) ),
+	};
+
+	let ( mut config, rest ) = Config::parse_args( args ).unwrap_or_else( |e| fail( e.to_string() ) );
+	let recording = Recording::from_config( &mut config ).unwrap_or_else( |e| fail( format!( "failed to read recording: {}", e ) ) );
+	let ( width, height ) = config.size( sketch.size() );
+	if width > u16::MAX as i32 || height > u16::MAX as i32 {
+		fail( format!( "--width and --height must be at most {}", u16::MAX ) );
+	}
+
+	let mut steps = 120;
+	let mut out = PathBuf::from( "frames" );
+	let mut gif = None;
+	let mut debug = false;
+	let mut timings = false;
+	let mut rest = rest.into_iter();
+	while let Some( flag ) = rest.next() {
+		match flag.as_str() {
+			"--steps" => steps = rest.next().and_then( |steps| steps.parse().ok() ).unwrap_or_else( || fail( "--steps needs a number".to_string() ) ),
+			"--out" => out = rest.next().map( PathBuf::from ).unwrap_or( out ),
+			"--gif" => gif = rest.next().map( PathBuf::from ),
+			"--debug" => debug = true,
+			"--timings" => timings = true,
+			_ => fail( format!( "unknown flag {}", flag ) ),
+		}
+	}
+
+	let output = match gif {
+		Some( path ) => GifRecorder::create( path, width as u16, height as u16, 30 ).map( Output::Gif ),
+		None => PngSequence::new( &out, &name ).map( Output::Png ),
+	};
+	let mut output = output.unwrap_or_else( |e| {
+		eprintln!( "failed to create output: {}", e );
+		exit( 1 );
+	});
+
+	let mut canvas = RasterCanvas::new( width as u32, height as u32 );
+	let mut ctx = Context::with_config( ( width, height ), config );
+	ctx.debug = debug;
+	match recording {
+		Some( recording ) => {
+			let mut replay = Replay::new( sketch.as_mut(), recording ).unwrap_or_else( |e| fail( format!( "failed to replay: {}", e ) ) );
+			capture( &mut replay, ctx, steps, timings, &mut canvas, &mut output );
+		}
+		None => capture( sketch.as_mut(), ctx, steps, timings, &mut canvas, &mut output ),
+	}
+
+	if let Output::Gif( gif ) = output {
+		if let Err( e ) = gif.finish() {
+			eprintln!( "failed to finish gif: {}", e );
+			exit( 1 );
+		}
+	}
+}
@@ -0,0 +1,50 @@
+use std::process::exit;
+use rusty_nature_of_code::config::Config;
+use rusty_nature_of_code::sketch::launcher::launch;
+use rusty_nature_of_code::sketch::registry;
+use rusty_nature_of_code::sketch::runner::run_with;
+
+fn usage() -> String {
+	let mut usage = format!( "usage: noc [list | run <{}>] [flags]\nflags:", registry::names() );
+	for ( flag, description ) in Config::FLAGS {
+		usage += &format!( "\n  {:<16} {}", flag, description );
+	}
+	usage
+}
+
+/// Parses the config flags, exiting with a message if any are invalid or unknown
+fn config( args: Vec<String> ) -> Config {
+	match Config::parse_args( args ) {
+		Ok( ( config, rest ) ) if rest.is_empty() => config,
+		Ok( ( _, rest ) ) => {
+			eprintln!( "unknown argument {}\n{}", rest[0], usage() );
+			exit( 2 );
+		},
+		Err( e ) => {
+			eprintln!( "{}\n{}", e, usage() );
+			exit( 2 );
+		}
+	}
+}
+
+fn main() {
+	let mut args: Vec<String> = std::env::args().skip(1).collect();
+	match args.first().map( String::as_str ) {
+		Some( "list" ) => {
+			for sketch in registry::sketches() {
+				println!( "{:<20} {}", sketch.name, sketch.description );
+			}
+		},
+		Some( "run" ) => {
+			let name = args.get( 1 ).cloned().unwrap_or_default();
+			let registration = registry::find( &name ).unwrap_or_else( || {
+				eprintln!( "unknown sketch {:?}\n{}", name, usage() );
+				exit( 2 );
+			});
+			let config = config( args.split_off( 2 ) );
+			run_with( ( registration.create )().as_mut(), config );
+		},
+		/* Without a command, opens the menu */
+		_ => launch( config( args ) ),
+	}
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::process::exit;
+use std::str::FromStr;
+use std::thread;
+use rusty_nature_of_code::smart_rockets::headless::*;
+
+const USAGE: &str = "usage: smart_rockets_sweep [--mutation 0.05,0.1] [--population 10,50] [--lifespan 10,20]
+                           [--seeds 1,2,3] [--generations N] [--threads N] [--out DIR]";
+
+/// Parses a comma separated list of values, exiting with the usage if any of them don't parse
+fn parse_list<T: FromStr>(flag: &str, value: &str) -> Vec<T> {
+	value.split(',').map(|item| parse(flag, item)).collect()
+}
+
+fn parse<T: FromStr>(flag: &str, value: &str) -> T {
+	value.trim().parse().unwrap_or_else(|_| {
+		eprintln!("invalid value for {}: {}\n{}", flag, value, USAGE);
+		exit(2);
+	})
+}
+
+fn main() {
+
+	/* Starts with the same parameters as the smart_rockets binary, then overrides them from the command line */
+	let mut sweep = Sweep::default();
+	let mut threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+	let mut out = PathBuf::from("sweep");
+
+	let mut args = std::env::args().skip(1);
+	while let Some(flag) = args.next() {
+		if flag == "--help" || flag == "-h" {
+			println!("{}", USAGE);
+			return;
+		}
+		let value = args.next().unwrap_or_else(|| {
+			eprintln!("missing value for {}\n{}", flag, USAGE);
+			exit(2);
+		});
+		match flag.as_str() {
+			"--mutation" => sweep.mutation_chances = parse_list(&flag, &value),
+			"--population" => sweep.populations = parse_list(&flag, &value),
+			"--lifespan" => sweep.lifespans = parse_list(&flag, &value),
+			"--seeds" => sweep.seeds = parse_list(&flag, &value),
+			"--generations" => sweep.generations = parse(&flag, &value),
+			"--threads" => threads = parse(&flag, &value),
+			"--out" => out = PathBuf::from(value),
+			_ => {
+				eprintln!("unknown flag {}\n{}", flag, USAGE);
+				exit(2);
+			}
+		}
+	}
+
+	/* Runs every configuration and prints the csv files written */
+	match sweep.run(&out, threads) {
+		Ok(paths) => {
+			for path in paths {
+				println!("{}", path.display());
+			}
+		}
+		Err(e) => {
+			eprintln!("sweep failed: {}", e);
+			exit(1);
+		}
+	}
+}
\ No newline at end of file
@@ -1,4 +1,14 @@
-use raylib::prelude::Color;
+use std::collections::HashMap;
+use misc_ecs::prelude::Entity;
+use miscmath::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::collider::Collider;
+use crate::color::Rgba;
+use crate::component;
+use crate::material::{Mat, Material};
+use crate::shape::{Shape, Shp};
+use crate::store::Components;
+use crate::world::{Acc, Bundle, Pos, Vel, World};
 
 /// A attributes struct holding characteristics of a given entity
 ///
@@ -8,15 +18,87 @@ use raylib::prelude::Color;
 ///
 /// ```
 ///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::empty_docs)]
 pub struct Attributes {
 	///
 	pub mass: f32,
 	///
-	pub color: Color,
+	pub color: Rgba,
 	///
 	pub radius: f32,
+	/// How far the entity is turned anticlockwise, in radians
+	#[serde(default)]
+	pub angle: f32,
+	/// How fast the entity is turning anticlockwise, in radians per pass
+	#[serde(default)]
+	pub spin: f32,
 	///
 	pub row: usize,
 	///
 	pub column: usize,
+}
+
+component!(
+	/// Attributes of a ball
+	Atr => Attributes,
+);
+
+/// The grid of "regions" the screen is split into, indexed by row then column. Each region is a hash map of the entities
+/// currently in it, keyed by their id, so collisions only need checking between entities in the same region
+pub type Regions = Vec<Vec<HashMap<u64, Entity>>>;
+
+/// Removes an entity from the region its attributes say it's in. A despawn hook, so despawned entities don't linger in
+/// the grid until it's next rebuilt
+pub fn leave_region( world: &World, entity: Entity ) {
+	if let Some( atr ) = world.store::<Atr>().get( entity ) {
+		if let Some( region ) = world.resource_mut::<Regions>().get_mut( atr.row ).and_then( | row | row.get_mut( atr.column ) ) {
+			region.remove( &entity.id() );
+		}
+	}
+}
+
+/// Creates an empty grid of regions with the given number of columns and rows
+pub fn regions( columns: usize, rows: usize ) -> Regions {
+	vec![ vec![ HashMap::new(); columns ]; rows ]
+}
+
+/// The components of a ball
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct BallBundle {
+	pub atr: Attributes,
+	pub pos: Vec2,
+	pub vel: Vec2,
+	pub acc: Vec2,
+	pub mat: Material,
+	/// Shape of the ball, a circle of its radius if None
+	pub shape: Option<Shape>,
+}
+
+impl Bundle for BallBundle {
+	fn insert( self, world: &World, entity: Entity ) {
+		world.store_mut::<Atr>().add( entity, self.atr );
+		world.store_mut::<Pos>().add( entity, self.pos );
+		world.store_mut::<Vel>().add( entity, self.vel );
+		world.store_mut::<Acc>().add( entity, self.acc );
+		world.store_mut::<Mat>().add( entity, self.mat );
+		if let Some( shape ) = self.shape {
+			world.store_mut::<Shp>().add( entity, shape );
+		}
+	}
+}
+
+/// A collider which doesn't move, which entities bounce off depending on its material
+#[derive(Clone, Debug, PartialEq)]
+pub struct Obstacle {
+	pub collider: Collider,
+	pub material: Material,
+	pub color: Rgba,
 }
\ No newline at end of file
@@ -1,3 +1,6 @@
 pub mod data;
 pub mod systems;
-pub mod prelude;
\ No newline at end of file
+pub mod scene;
+pub mod prelude;
+#[cfg(feature = "parallel")]
+pub mod parallel;
\ No newline at end of file
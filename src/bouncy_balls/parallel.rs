@@ -0,0 +1,150 @@
+//! Parallel variants of the per entity bouncy balls systems, which split the dense stores across rayon's threads.
+//!
+//! The stores a system changes are aligned, so their components line up and can be zipped together, and the ones it only
+//! reads are looked up by entity. If the stores it changes don't hold the same entities the serial system runs instead,
+//! which the sketches never hit as balls are spawned and despawned with all their components.
+//!
+//! Whatever the entities share, the regions, boundary crossings and collision pairs, is gathered per entity and merged in the
+//! stores' order once every thread is done, so a seeded run is the same with or without the parallel feature.
+
+use super::data::*;
+use super::systems::*;
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use rayon::prelude::*;
+use crate::boundary::*;
+use crate::events::*;
+use crate::material::*;
+use crate::shape::Shape;
+use crate::store::{Components, DenseStore};
+use std::collections::HashMap;
+
+/// Applies the acceleration components to the velocity components, across threads
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn par_acceleration_system( acc_store: &mut DenseStore<Vec2>, vel_store: &mut DenseStore<Vec2> ) {
+	if !acc_store.align( vel_store.entities() ) {
+		return acceleration_system( acc_store, vel_store );
+	}
+
+	vel_store.items_mut().par_iter_mut().zip( acc_store.items_mut().par_iter_mut() ).for_each( |( vel, acc )| {
+		/* Adds acc to vel, then zeroes out the acceleration */
+		vel.add( acc );
+		*acc = Vec2::default();
+	});
+}
+
+/// Applies the vel to each corresponding pos across threads, then moves each entity to the region it's now in
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn par_movement_system( screen_size: (i32, i32),
+							columns: usize,
+							rows: usize,
+							vel_store: &mut DenseStore<Vec2>,
+							pos_store: &mut DenseStore<Vec2>,
+							atr_store: &mut DenseStore<Attributes>,
+							regions: &mut [Vec<HashMap<u64, Entity>>] ) {
+	if !atr_store.align( pos_store.entities() ) {
+		return movement_system( screen_size, columns, rows, vel_store, pos_store, atr_store, regions );
+	}
+	let screen_size = ( screen_size.0 as f32, screen_size.1 as f32 );
+	let vel_store = &*vel_store;
+	let ( entities, positions ) = pos_store.parts_mut();
+
+	/* Moves each entity with a velocity, keeping the region it was in, as the regions can only be changed by one thread */
+	let moved: Vec<Option<(usize, usize)>> = entities.par_iter().zip( positions.par_iter_mut() ).zip( atr_store.items_mut().par_iter_mut() )
+		.map( |( ( entity, pos ), atr )| {
+			let vel = vel_store.get( *entity )?;
+			let region = ( atr.row, atr.column );
+			step( screen_size, columns, rows, pos, atr, vel );
+			Some( region )
+		})
+		.collect();
+
+	/* Moves the entities which moved from the region they were in to the one they're in now */
+	for ( ( entity, region ), atr ) in entities.iter().zip( moved ).zip( atr_store.items() ) {
+		if let Some( ( row, column ) ) = region {
+			regions[row][column].remove( &entity.id() );
+			regions[atr.row][atr.column].insert( entity.id(), *entity );
+		}
+	}
+}
+
+/// Applies the boundary's edges to each entity across threads, returning what happened at them in the order the serial
+/// system finds them
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn par_boundary_system( screen_size: (i32, i32),
+							boundary: &Boundary,
+							vel_store: &mut DenseStore<Vec2>,
+							pos_store: &mut DenseStore<Vec2>,
+							atr_store: &mut DenseStore<Attributes>,
+							mat_store: &DenseStore<Material>,
+							shp_store: &DenseStore<Shape> ) -> Vec<Crossing> {
+	if !vel_store.align( pos_store.entities() ) || !atr_store.align( pos_store.entities() ) {
+		return boundary_system( screen_size, boundary, vel_store, pos_store, atr_store, mat_store, shp_store );
+	}
+	let screen_size = ( screen_size.0 as f32, screen_size.1 as f32 );
+	let ( entities, positions ) = pos_store.parts_mut();
+
+	entities.par_iter().zip( positions.par_iter_mut() ).zip( vel_store.items_mut().par_iter_mut() ).zip( atr_store.items_mut().par_iter_mut() )
+		.flat_map_iter( |( ( ( entity, pos ), vel ), atr )| {
+			let mut crossings = Vec::new();
+			let mat = mat_store.get( *entity ).copied().unwrap_or_default();
+			let crossed = |side, crossed| crossings.push( Crossing { entity: *entity, side, crossed } );
+			match shp_store.get( *entity ) {
+				Some( shape ) => {
+					let mut body = motion( pos, vel, atr, Some( shape ) );
+					boundary.apply_body( screen_size, shape, atr.angle, &mat, &mut body, crossed );
+					( *pos, *vel, atr.spin ) = ( body.pos, body.vel, body.spin );
+				}
+				None => boundary.apply( screen_size, atr.radius, &mat, pos, vel, crossed ),
+			}
+			crossings
+		})
+		.collect()
+}
+
+/// Finds the colliding pairs in each region across threads, then bounces the entities which collide off each other,
+/// returning a Collision for each pair resolved
+///
+/// Each pair is found from both of its entities, but only resolved once, as the first leaves them moving apart.
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn par_collision_system( vel_store: &mut DenseStore<Vec2>,
+							 pos_store: &mut DenseStore<Vec2>,
+							 atr_store: &mut DenseStore<Attributes>,
+							 mat_store: &DenseStore<Material>,
+							 shp_store: &DenseStore<Shape>,
+							 regions: &[Vec<HashMap<u64, Entity>>] ) -> Vec<Collision> {
+	/* The pairs are merged in the position store's order, then sorted, so are resolved in the same order as serially */
+	let collisions = pos_store.entities().par_iter().zip( pos_store.items().par_iter() )
+		.flat_map_iter( |( entity_a, pos_a )| {
+			let mut pairs = Vec::new();
+			region_pairs( *entity_a, pos_a, pos_store, &*atr_store, regions, &mut pairs );
+			pairs
+		})
+		.collect();
+
+	resolve_collisions( collisions, vel_store, pos_store, atr_store, mat_store, shp_store )
+}
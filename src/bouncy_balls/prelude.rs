@@ -1,2 +1,6 @@
 pub use crate::bouncy_balls::systems::*;
-pub use crate::bouncy_balls::data::*;
\ No newline at end of file
+pub use crate::bouncy_balls::data::*;
+pub use crate::collider::*;
+pub use crate::color::Rgba;
+pub use crate::material::*;
+pub use crate::shape::*;
\ No newline at end of file
@@ -0,0 +1,393 @@
+//! Scenes describe a bouncy balls setup in a TOML file: the starting balls, how new balls are spawned, gravity, the edges
+//! and obstacles. A scene can be loaded into a World, and the live state saved back out.
+//!
+//! ```toml
+//! gravity = 0.0
+//!
+//! [boundary]
+//! left = "wrap"
+//! right = "wrap"
+//! top = "despawn"
+//!
+//! [boundary.bottom.bounce]
+//! restitution = 1.0
+//! static_friction = 0.0
+//! dynamic_friction = 0.0
+//!
+//! [[balls]]
+//! pos = [100.0, 240.0]
+//! vel = [4.0, 0.0]
+//! radius = 15.0
+//! color = "#e62937"
+//! material = { restitution = 0.9, static_friction = 1.0, dynamic_friction = 0.8 }
+//!
+//! [[obstacles]]
+//! pos = [320.0, 240.0]
+//! radius = 40.0
+//!
+//! [[obstacles]]
+//! segment = [[0.0, 200.0], [200.0, 100.0]]
+//!
+//! [[obstacles]]
+//! aabb = [[400.0, 0.0], [420.0, 80.0]]
+//!
+//! [[obstacles]]
+//! polygon = [[500.0, 0.0], [640.0, 0.0], [640.0, 100.0]]
+//! material = { restitution = 0.5, static_friction = 0.5, dynamic_friction = 0.4 }
+//! ```
+
+use std::f32::consts::{PI, TAU};
+use std::fs;
+use std::io;
+use std::path::Path;
+use miscmath::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::color::Rgba;
+use crate::config::BouncyBallsConfig;
+use crate::boundary::Boundary;
+use crate::collider::Collider;
+use crate::material::Material;
+use crate::rng::SimRng;
+use crate::shape::Shape;
+use crate::store::Components;
+use crate::world::World;
+use super::data::*;
+
+/// A ball in a scene, positions are in world coordinates with (0,0) at the bottom left
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ball {
+	pub pos: [f32; 2],
+	#[serde(default)]
+	pub vel: [f32; 2],
+	#[serde(default = "Ball::default_mass")]
+	pub mass: f32,
+	/// Radius of a circular ball, balls with a shape take the radius of the circle around it
+	#[serde(default = "Ball::default_radius")]
+	pub radius: f32,
+	#[serde(default = "Ball::default_color")]
+	pub color: Rgba,
+	#[serde(default)]
+	pub material: Material,
+	/// Shape of the ball, a circle of its radius if left out
+	#[serde(default)]
+	pub shape: Option<Shape>,
+	/// How far the ball is turned anticlockwise, in radians
+	#[serde(default)]
+	pub angle: f32,
+	/// How fast the ball is turning anticlockwise, in radians per pass
+	#[serde(default)]
+	pub spin: f32,
+}
+
+impl Ball {
+	fn default_mass() -> f32 { 1.0 }
+	fn default_radius() -> f32 { 12.0 }
+	fn default_color() -> Rgba { Rgba::WHITE }
+}
+
+/// Keeps a number of balls on screen, spawning random ones as others are dropped. Ranges are [min, max]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Spawn {
+	/// Number of balls to keep on screen, counting the scene's starting balls
+	pub count: usize,
+	pub mass: [f32; 2],
+	pub radius: [f32; 2],
+	/// Range of x positions, the width of the screen if left out
+	pub x: Option<[f32; 2]>,
+	/// Range of y positions, the top 100 pixels of the screen if left out
+	pub y: Option<[f32; 2]>,
+	/// Speed balls are spawned with
+	pub speed: f32,
+	/// Range of directions balls are spawned moving in, in radians anticlockwise from the right
+	pub angle: [f32; 2],
+	/// Material balls are spawned with
+	pub material: Material,
+	/// Shape balls are spawned with, turned to a random angle, or circles of a random radius if left out
+	pub shape: Option<Shape>,
+}
+
+impl Default for Spawn {
+	fn default() -> Self {
+		Spawn { count: 100, mass: [1.01, 1.56], radius: [10.0, 15.0], x: None, y: None, speed: 5.0, angle: [PI, TAU], material: Material::default(), shape: None }
+	}
+}
+
+/// An obstacle in a scene, a shape which doesn't move. Each obstacle has one shape: a circle with a pos and radius, a
+/// segment, an aabb or a polygon
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObstacleSpec {
+	/// Center of a circle
+	#[serde(default)]
+	pub pos: Option<[f32; 2]>,
+	/// Radius of a circle
+	#[serde(default)]
+	pub radius: Option<f32>,
+	/// Ends of a line segment
+	#[serde(default)]
+	pub segment: Option<[[f32; 2]; 2]>,
+	/// Bottom left and top right corners of an axis aligned box
+	#[serde(default)]
+	pub aabb: Option<[[f32; 2]; 2]>,
+	/// Points of a convex polygon, in either order around it
+	#[serde(default)]
+	pub polygon: Option<Vec<[f32; 2]>>,
+	#[serde(default = "ObstacleSpec::default_color")]
+	pub color: Rgba,
+	#[serde(default = "ObstacleSpec::default_material")]
+	pub material: Material,
+}
+
+impl ObstacleSpec {
+	fn default_color() -> Rgba { Rgba::new( 80, 80, 80, 255 ) }
+	fn default_material() -> Material { Material::WALL }
+
+	/// The obstacle's shape, or None if it doesn't have exactly one
+	pub fn collider( &self ) -> Option<Collider> {
+		let vec2 = |[x, y]: [f32; 2]| Vec2::new( &x, &y );
+		let shapes = [
+			self.pos.zip( self.radius ).map( |( pos, radius )| Collider::Circle { pos: vec2( pos ), radius } ),
+			self.segment.map( |[from, to]| Collider::Segment { from: vec2( from ), to: vec2( to ) } ),
+			self.aabb.map( |[min, max]| Collider::Aabb { min: vec2( min ), max: vec2( max ) } ),
+			self.polygon.as_ref().map( |points| Collider::polygon( points.iter().copied().map( vec2 ).collect() ) ),
+		];
+		/* A pos or radius on its own isn't a circle, so it's another shape too many */
+		let partial_circle = self.pos.is_some() != self.radius.is_some();
+		match shapes.iter().flatten().count() == 1 && !partial_circle {
+			true => shapes.into_iter().flatten().next(),
+			false => None,
+		}
+	}
+}
+
+/// Everything needed to set up bouncy balls
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::bouncy_balls::data::Atr;
+/// use rusty_nature_of_code::material::Mat;
+/// use rusty_nature_of_code::shape::Shp;
+/// use rusty_nature_of_code::bouncy_balls::scene::Scene;
+/// use rusty_nature_of_code::world::*;
+///
+/// let scene = Scene::from_toml( r##"
+/// gravity = 0.0
+///
+/// [[balls]]
+/// pos = [100.0, 200.0]
+/// vel = [3.0, 0.0]
+/// color = "#ff0000"
+/// "## ).unwrap();
+///
+/// let mut world = World::new();
+/// world.register::<Atr>().register::<Pos>().register::<Vel>().register::<Acc>().register::<Mat>().register::<Shp>();
+/// scene.populate( &world );
+///
+/// let saved = scene.capture( &*world.store::<Pos>(), &*world.store::<Vel>(), &*world.store::<Atr>(), &*world.store::<Mat>(), &*world.store::<Shp>() );
+/// assert_eq!( saved, scene );
+/// assert_eq!( Scene::from_toml( &saved.to_toml() ).unwrap(), scene );
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+	/// Acceleration applied to every ball each pass, negative is down
+	#[serde(default = "Scene::default_gravity")]
+	pub gravity: f32,
+	/// Largest speed a ball can have along each axis
+	#[serde(default = "Scene::default_max_speed")]
+	pub max_speed: f32,
+	/// How new balls are spawned. Scene files without a [spawn] table only have their starting balls
+	#[serde(default)]
+	pub spawn: Option<Spawn>,
+	/// What each edge of the screen does to balls reaching it, bouncing walls if left out
+	#[serde(default)]
+	pub boundary: Boundary,
+	#[serde(default)]
+	pub balls: Vec<Ball>,
+	#[serde(default)]
+	pub obstacles: Vec<ObstacleSpec>,
+}
+
+impl Default for Scene {
+	/// The scene bouncy balls has always run, 100 random balls falling from the top of the screen
+	fn default() -> Self {
+		Scene::from_config( &BouncyBallsConfig::default() )
+	}
+}
+
+/// Converts a TOML error to an io error, so scenes only have one error type
+fn invalid_data<E: std::fmt::Display>( e: E ) -> io::Error {
+	io::Error::new( io::ErrorKind::InvalidData, e.to_string() )
+}
+
+impl Scene {
+	fn default_gravity() -> f32 { BouncyBallsConfig::default().gravity }
+	fn default_max_speed() -> f32 { BouncyBallsConfig::default().max_speed }
+
+	/// The default scene, with the ball count, gravity and max speed from config
+	pub fn from_config( config: &BouncyBallsConfig ) -> Scene {
+		Scene {
+			gravity: config.gravity,
+			max_speed: config.max_speed,
+			spawn: Some( Spawn { count: config.balls, ..Spawn::default() } ),
+			boundary: Boundary::default(),
+			balls: Vec::new(),
+			obstacles: Vec::new(),
+		}
+	}
+
+	/// Parses and validates a scene from TOML
+	pub fn from_toml( toml: &str ) -> io::Result<Scene> {
+		let scene: Scene = toml::from_str( toml ).map_err( invalid_data )?;
+		scene.validate()?;
+		Ok( scene )
+	}
+
+	/// Reads a scene from a TOML file
+	pub fn load<P: AsRef<Path>>( path: P ) -> io::Result<Scene> {
+		Scene::from_toml( &fs::read_to_string( path )? )
+	}
+
+	/// The scene as TOML
+	pub fn to_toml( &self ) -> String {
+		toml::to_string( self ).expect( "every scene without custom edges can be written as toml" )
+	}
+
+	/// Writes the scene to a TOML file
+	pub fn save<P: AsRef<Path>>( &self, path: P ) -> io::Result<()> {
+		fs::write( path, self.to_toml() )
+	}
+
+	/// Checks every value is in range
+	pub fn validate( &self ) -> io::Result<()> {
+		let invalid = |message: String| Err( io::Error::new( io::ErrorKind::InvalidData, message ) );
+		let range = |name: &str, [min, max]: [f32; 2], positive: bool| if min.is_finite() && max.is_finite() && min <= max && ( min > 0.0 || !positive ) {
+			Ok(())
+		} else {
+			invalid( format!( "spawn.{} must be [min, max]{}", name, if positive { " with min greater than 0" } else { "" } ) )
+		};
+
+		if !self.gravity.is_finite() {
+			return invalid( "gravity must be a finite number".to_string() );
+		}
+		if !( self.max_speed.is_finite() && self.max_speed > 0.0 ) {
+			return invalid( "max_speed must be greater than 0".to_string() );
+		}
+		if let Some( spawn ) = &self.spawn {
+			range( "mass", spawn.mass, true )?;
+			range( "radius", spawn.radius, true )?;
+			range( "angle", spawn.angle, false )?;
+			if let Some( x ) = spawn.x { range( "x", x, false )?; }
+			if let Some( y ) = spawn.y { range( "y", y, false )?; }
+			if !spawn.speed.is_finite() {
+				return invalid( "spawn.speed must be a finite number".to_string() );
+			}
+			if !spawn.material.is_valid() {
+				return invalid( "spawn.material must have a restitution and frictions of at least 0".to_string() );
+			}
+			if spawn.shape.as_ref().is_some_and( |shape| !shape.is_valid() ) {
+				return invalid( "spawn.shape must have a size greater than 0, or be a convex polygon with its points anticlockwise".to_string() );
+			}
+		}
+		if !self.boundary.is_valid() {
+			return invalid( "boundary edges must have restitutions and frictions of at least 0".to_string() );
+		}
+
+		for ( i, ball ) in self.balls.iter().enumerate() {
+			if !( ball.mass.is_finite() && ball.mass > 0.0 && ball.radius.is_finite() && ball.radius > 0.0 ) {
+				return invalid( format!( "balls[{}] must have a mass and radius greater than 0", i ) );
+			}
+			if !ball.material.is_valid() {
+				return invalid( format!( "balls[{}] must have a restitution and frictions of at least 0", i ) );
+			}
+			if ball.shape.as_ref().is_some_and( |shape| !shape.is_valid() ) || !ball.angle.is_finite() || !ball.spin.is_finite() {
+				return invalid( format!( "balls[{}] must have a shape with a size greater than 0, or a convex polygon with its points anticlockwise, and a finite angle and spin", i ) );
+			}
+		}
+		for ( i, obstacle ) in self.obstacles.iter().enumerate() {
+			match obstacle.collider() {
+				None => return invalid( format!( "obstacles[{}] must have one shape, a pos and radius, segment, aabb or polygon", i ) ),
+				Some( collider ) if !collider.is_valid() => return invalid( format!(
+					"obstacles[{}] must have a radius greater than 0, an aabb with min below and left of max, or a convex polygon", i ) ),
+				_ => {}
+			}
+			if !obstacle.material.is_valid() {
+				return invalid( format!( "obstacles[{}] must have a restitution and frictions of at least 0", i ) );
+			}
+		}
+		Ok(())
+	}
+
+	/// The scene's obstacles, leaving out any without exactly one shape, which validate rejects
+	pub fn obstacles( &self ) -> Vec<Obstacle> {
+		self.obstacles.iter()
+			.filter_map( |obstacle| Some( Obstacle { collider: obstacle.collider()?, material: obstacle.material, color: obstacle.color } ) )
+			.collect()
+	}
+
+	/// Spawns the scene's starting balls
+	pub fn populate( &self, world: &World ) {
+		for ball in self.balls.iter() {
+			let radius = ball.shape.as_ref().map_or( ball.radius, Shape::radius );
+			world.spawn( BallBundle {
+				atr: Attributes { mass: ball.mass, color: ball.color, radius, angle: ball.angle, spin: ball.spin, row: 0, column: 0 },
+				pos: Vec2::new( &ball.pos[0], &ball.pos[1] ),
+				vel: Vec2::new( &ball.vel[0], &ball.vel[1] ),
+				acc: Vec2::default(),
+				mat: ball.material,
+				shape: ball.shape.clone(),
+			});
+		}
+	}
+
+	/// Spawns random balls following the spawn rules until there are spawn.count entities, if the scene has spawn rules
+	pub fn spawn( &self, screen_size: ( i32, i32 ), rng: &mut SimRng, world: &World ) {
+		let Some( spawn ) = &self.spawn else {
+			return;
+		};
+		let ( width, height ) = ( screen_size.0 as f32, screen_size.1 as f32 );
+		/* Ranges where min and max are equal can't be sampled, so they just give min */
+		let pick = |rng: &mut SimRng, [min, max]: [f32; 2]| if min < max { rng.range( min..max ) } else { min };
+		let [x, y] = [ spawn.x.unwrap_or( [0.0, width] ), spawn.y.unwrap_or( [height - 100.0, height] ) ];
+
+		while world.len() < spawn.count {
+			/* Generates a random color */
+			let color = Rgba::new( rng.range(100..255), rng.range(100..255), rng.range(100..255), 255 );
+
+			let ( radius, angle ) = match &spawn.shape {
+				Some( shape ) => ( shape.radius(), rng.range( 0.0..TAU ) ),
+				None => ( pick( rng, spawn.radius ), 0.0 ),
+			};
+			world.spawn( BallBundle {
+				atr: Attributes { mass: pick( rng, spawn.mass ), radius, angle, spin: 0.0, color, row: 0, column: 0 },
+				pos: Vec2::new( &pick( rng, x ), &pick( rng, y ) ),
+				vel: Vec2::from_angle( &pick( rng, spawn.angle ), &Some( spawn.speed ) ),
+				acc: Vec2::default(),
+				mat: spawn.material,
+				shape: spawn.shape.clone(),
+			});
+		}
+	}
+
+	/// A copy of the scene with the live balls in place of the starting balls, so the current state can be saved and loaded later
+	///
+	/// Balls are sorted by position, so the same state always saves the same file.
+	pub fn capture< P: Components<Vec2>, V: Components<Vec2>, AT: Components<Attributes>, M: Components<Material>, S: Components<Shape> >( &self, pos_store: &P, vel_store: &V, atr_store: &AT,
+																												   mat_store: &M, shp_store: &S ) -> Scene {
+		let mut balls = Vec::new();
+		pos_store.for_each( |entity, pos| {
+			if let Some( atr ) = atr_store.get( entity ) {
+				let vel = vel_store.get( entity ).copied().unwrap_or_default();
+				let material = mat_store.get( entity ).copied().unwrap_or_default();
+				balls.push( Ball { pos: [pos.x, pos.y], vel: [vel.x, vel.y], mass: atr.mass, radius: atr.radius, color: atr.color, material,
+								   shape: shp_store.get( entity ).cloned(), angle: atr.angle, spin: atr.spin } );
+			}
+		});
+		balls.sort_by( |a, b| a.pos[0].total_cmp( &b.pos[0] ).then( a.pos[1].total_cmp( &b.pos[1] ) ) );
+		Scene { balls, ..self.clone() }
+	}
+}
@@ -1,7 +1,15 @@
 use super::data::*;
 use miscmath::prelude::*;
 use misc_ecs::prelude::*;
-use raylib::prelude::*;
+use crate::boundary::*;
+use crate::canvas::Canvas;
+use crate::collider::*;
+use crate::color::Rgba;
+use crate::events::*;
+use crate::material::*;
+use crate::query::*;
+use crate::shape::*;
+use crate::store::Components;
 use std::collections::HashMap;
 
 /// Applies the vel to each corresponding pos, then calculates the region, or grid that current entity is in
@@ -12,51 +20,54 @@ use std::collections::HashMap;
 ///
 /// ```
 ///
-pub fn movement_system< V: Store<Vec2>, P: Store<Vec2>, A: Store<Attributes> >(screen_size: (i32, i32),
+pub fn movement_system< V: Components<Vec2>, P: Components<Vec2>, A: Components<Attributes> >(screen_size: (i32, i32),
 																			   columns: usize,
 																			   rows: usize,
 																			   vel_store: &mut V,
 																			   pos_store: &mut P,
 																			   atr_store: &mut A,
-																			   regions: &mut [[HashMap<u64, Entity>; 10]; 10] ) {
+																			   regions: &mut [Vec<HashMap<u64, Entity>>] ) {
 	/* Converts the screen_size tuple to f32 to be used in calculations */
 	let screen_size = ( screen_size.0 as f32, screen_size.1 as f32 );
 	
-	/* Apply a closure to each pos in the pos component store */
-	pos_store.for_each_mut( | entity, pos | {
+	/* Apply a closure to each entity with a position, attributes and velocity */
+	query( ( write( pos_store ), write( atr_store ), read( vel_store ) ) ).for_each( | entity, ( pos, atr, vel ) | {
 		
-		/* Gets the attribute component for the current entity */
-		let atr = atr_store.get_mut( entity ).unwrap();
-		
-		/* If current entity has a velocity component, assign it to vel and run the following code block */
-		if let Some( vel ) = vel_store.get_mut( entity ) {
-			
-			/* Adds the velocity component to the position component */
-			pos.add( vel );
-			
-			/* Removes the current entity from it's current region (it may still be in this region and will be
-			   added back or it may have moved to a new region) */
-			regions[atr.row][atr.column].remove( &entity.id() );
-
-			/* Calculates the column number of the current region or grid and clamps that value to the
-			   max number of columns */
-			let mut col = ( (pos.x / screen_size.0) * 10.0 ) as usize;
-			col = col.clamp( 1, columns - 1 );
-			atr.column = col;
-
-			/* Calculates the row number of the current region or grid and clamps that value to the
-			   max number of rows */
-			let mut row = ( (pos.y / screen_size.1) * 10.0 ) as usize;
-			row = row.clamp( 1, rows - 1 );
-			atr.row = row;
-
-			/* Inserts the entity into the map, with its u64 id value as the key */
-			regions[row][col].insert( entity.id(), entity );
-		}
+		/* Removes the current entity from it's current region (it may still be in this region and will be
+		   added back or it may have moved to a new region) */
+		regions[atr.row][atr.column].remove( &entity.id() );
+
+		step( screen_size, columns, rows, pos, atr, vel );
+
+		/* Inserts the entity into the map, with its u64 id value as the key */
+		regions[atr.row][atr.column].insert( entity.id(), entity );
 	});
 }
 
-/// Bounds entities position to be within the screen, and inverts their velocities when they hit the edges
+/// Moves one entity by its velocity and turns it by its spin, then sets the row and column of the region it's now in
+pub(crate) fn step( screen_size: (f32, f32), columns: usize, rows: usize, pos: &mut Vec2, atr: &mut Attributes, vel: &Vec2 ) {
+	/* Adds the velocity component to the position component */
+	pos.add( vel );
+	atr.angle = ( atr.angle + atr.spin ).rem_euclid( std::f32::consts::TAU );
+
+	/* Calculates the column number of the current region or grid and clamps that value to the
+	   max number of columns */
+	let mut col = ( (pos.x / screen_size.0) * columns as f32 ) as usize;
+	col = col.clamp( 1, columns - 1 );
+	atr.column = col;
+
+	/* Calculates the row number of the current region or grid and clamps that value to the
+	   max number of rows */
+	let mut row = ( (pos.y / screen_size.1) * rows as f32 ) as usize;
+	row = row.clamp( 1, rows - 1 );
+	atr.row = row;
+}
+
+/// Applies the boundary's edges to each entity, returning what happened at them
+///
+/// Bouncing off a solid edge combines its material with the entity's, or the default material if it doesn't have one.
+/// Entities with a shape reach as far towards each edge as their turned shape does, and are spun by bouncing off an edge
+/// away from their centre. Others reach as far as their radius.
 ///
 /// # Examples
 ///
@@ -64,35 +75,120 @@ pub fn movement_system< V: Store<Vec2>, P: Store<Vec2>, A: Store<Attributes> >(s
 ///
 /// ```
 ///
-pub fn boundary_system< V: Store<Vec2>, P: Store<Vec2>, A: Store<Attributes> >( screen_size: (i32, i32),
-																				vel_store: &mut V,
-																				pos_store: &mut P,
-																				atr_store: &A ) {
+pub fn boundary_system< V: Components<Vec2>, P: Components<Vec2>, A: Components<Attributes>, M: Components<Material>, S: Components<Shape> >( screen_size: (i32, i32),
+																													  boundary: &Boundary,
+																													  vel_store: &mut V,
+																													  pos_store: &mut P,
+																													  atr_store: &mut A,
+																													  mat_store: &M,
+																													  shp_store: &S ) -> Vec<Crossing> {
 	/* Converts screen_size tuple to f32 for use in calculations */
 	let screen_size = ( screen_size.0 as f32, screen_size.1 as f32 );
+	let mut crossings = Vec::new();
 	
-	/* Applies a closure to each entity with a pos component */
-	pos_store.for_each_mut( | entity, pos | {
-		
-		/* Gets current entities attributes component */
-		let atr = atr_store.get( entity ).unwrap();
-
-		/* If current entity has a velocity component, assign it to vel then run the following code block */
-		if let Some( vel ) = vel_store.get_mut( entity ) {
-			
-			/* Constrain entities position to within the screen, factoring in the size of the entity */
-			pos.constrain( &((atr.radius)..(screen_size.0 - atr.radius)), &((atr.radius)..(screen_size.1 - atr.radius)) );
-
-			/* If entity hits the edges, invert their velocity and reduce it based on mass, then reduce it based on surface friction */
-			if pos.x - atr.radius < 0.00000001 { vel.x *= -1.0 / atr.mass; vel.y *= 0.99; }
-			if pos.y - atr.radius < 0.00000001 { vel.y *= -1.0 / atr.mass; vel.x *= 0.9; }
-			if pos.x > screen_size.0 - atr.radius - 0.0001 { vel.x *= -1.0 / atr.mass; vel.y *= 0.99; }
-			if pos.y > screen_size.1 - atr.radius - 0.0001 { vel.y *= -1.0 / atr.mass; vel.x *= 0.95; }
+	/* Applies a closure to each entity with a position, velocity and attributes */
+	query( ( write( pos_store ), write( vel_store ), write( atr_store ), optional( read( mat_store ) ), optional( read( shp_store ) ) ) ).for_each( | entity, ( pos, vel, atr, mat, shape ) | {
+		let ( material, crossed ) = ( mat.copied().unwrap_or_default(), |side, crossed| crossings.push( Crossing { entity, side, crossed } ) );
+		match shape {
+			Some( shape ) => {
+				let mut body = motion( pos, vel, atr, Some( shape ) );
+				boundary.apply_body( screen_size, shape, atr.angle, &material, &mut body, crossed );
+				( *pos, *vel, atr.spin ) = ( body.pos, body.vel, body.spin );
+			}
+			None => boundary.apply( screen_size, atr.radius, &material, pos, vel, crossed ),
 		}
 	});
+	crossings
 	
 }
 
+/// How an entity moves, for working out the impulses at its contacts. Its moment of inertia comes from its shape and
+/// mass, or from a circle of its radius if it doesn't have a shape
+pub(crate) fn motion( pos: &Vec2, vel: &Vec2, atr: &Attributes, shape: Option<&Shape> ) -> Motion {
+	let inertia = shape.map_or( atr.mass * atr.radius * atr.radius / 2.0, |shape| shape.inertia( atr.mass ) );
+	Motion { pos: *pos, vel: *vel, spin: atr.spin, inv_mass: 1.0 / atr.mass, inv_inertia: 1.0 / inertia }
+}
+
+/// How far an entity reaches from its position towards each side, as far as its turned shape does, or its radius if it
+/// doesn't have one
+pub(crate) fn reach<'a>( atr: &'a Attributes, shape: Option<&'a Shape> ) -> impl Fn( Side ) -> f32 + 'a {
+	move |side| shape.map_or( atr.radius, |shape| shape.extent( atr.angle, &side.outward() ) )
+}
+
+/// Pushes entities out of any obstacle they overlap, and bounces them off the obstacle's surface along its normal, combining
+/// their materials. Entities with a shape collide with obstacles using it, and are spun by bouncing off where their shape
+/// touches the obstacle. Others collide as circles of their radius
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use misc_ecs::prelude::*;
+/// use rusty_nature_of_code::bouncy_balls::prelude::*;
+///
+/// let mut entity_manager = EntityManager::new();
+/// let ( mut vel_store, mut pos_store, mut atr_store ) = ( HashStore::new(), HashStore::new(), HashStore::new() );
+/// let ( mat_store, shp_store ) = ( HashStore::new(), HashStore::new() );
+/// let entity = entity_manager.next();
+/// pos_store.add( entity, Vec2::new( &0.0, &15.0 ) );
+/// vel_store.add( entity, Vec2::new( &0.0, &-2.0 ) );
+/// atr_store.add( entity, Attributes { mass: 1.0, color: Rgba::WHITE, radius: 10.0, angle: 0.0, spin: 0.0, row: 0, column: 0 } );
+///
+/// /* Entities without a material use the default one, which keeps most of their speed off a wall */
+/// let obstacles = [ Obstacle { collider: Collider::Circle { pos: Vec2::new( &0.0, &0.0 ), radius: 10.0 }, material: Material::WALL, color: Rgba::WHITE } ];
+/// obstacle_system( &obstacles, &mut vel_store, &mut pos_store, &mut atr_store, &mat_store, &shp_store );
+///
+/// assert_eq!( *pos_store.get( entity ).unwrap(), Vec2::new( &0.0, &20.0 ) );
+/// assert!( ( vel_store.get( entity ).unwrap().y - 1.6 ).abs() < 0.0001 );
+/// ```
+///
+pub fn obstacle_system< V: Components<Vec2>, P: Components<Vec2>, A: Components<Attributes>, M: Components<Material>, S: Components<Shape> >( obstacles: &[Obstacle],
+																													  vel_store: &mut V,
+																													  pos_store: &mut P,
+																													  atr_store: &mut A,
+																													  mat_store: &M,
+																													  shp_store: &S ) {
+	query( ( write( pos_store ), write( vel_store ), write( atr_store ), optional( read( mat_store ) ), optional( read( shp_store ) ) ) ).for_each( | _, ( pos, vel, atr, mat, shape ) | {
+		let material = mat.copied().unwrap_or_default();
+		for obstacle in obstacles {
+			let hit = match shape {
+				/* The manifold's normal points from the entity into the obstacle, so is turned around to push the entity out.
+				   Shapes also keep where they touch the obstacle, to be bounced off there */
+				Some( shape ) if obstacle.collider.near( pos, atr.radius ) => {
+					let ( obstacle_shape, obstacle_pos ) = obstacle_shape( &obstacle.collider );
+					collide( shape, pos, atr.angle, &obstacle_shape, &obstacle_pos, 0.0 ).map( |manifold| {
+						( Contact { normal: Vec2::new( &-manifold.normal.x, &-manifold.normal.y ), depth: manifold.depth }, manifold.centre() )
+					})
+				}
+				Some( _ ) => None,
+				None => obstacle.collider.contact( pos, atr.radius ).map( |contact| ( contact, None ) ),
+			};
+			let Some( ( contact, point ) ) = hit else {
+				continue;
+			};
+
+			/* Pushes the entity out along the normal, then bounces it off if it's heading into the obstacle. Obstacles don't
+			   move, so like walls the entity's mass cancels out */
+			pos.x += contact.normal.x * contact.depth;
+			pos.y += contact.normal.y * contact.depth;
+			let material = material.combine( &obstacle.material );
+			match point {
+				/* The point where the shape touches moves out along with it */
+				Some( point ) => {
+					let point = Vec2::new( &( point.x + contact.normal.x * contact.depth ), &( point.y + contact.normal.y * contact.depth ) );
+					let mut body = motion( pos, vel, atr, shape );
+					if rigid_impulse( &mut Motion::fixed( point ), &mut body, &point, &contact.normal, &material ).is_some() {
+						( *vel, atr.spin ) = ( body.vel, body.spin );
+					}
+				}
+				None => if let Some( impulse ) = contact_impulse( vel, &contact.normal, 1.0, &material ) {
+					vel.add( &impulse );
+				},
+			}
+		}
+	});
+}
+
 /// Applies the acceleration components to the velocity components
 ///
 /// # Examples
@@ -101,7 +197,7 @@ pub fn boundary_system< V: Store<Vec2>, P: Store<Vec2>, A: Store<Attributes> >(
 ///
 /// ```
 ///
-pub fn acceleration_system< A: Store<Vec2>, V: Store<Vec2> >(acc_store: &mut A, vel_store: &mut V ) {
+pub fn acceleration_system< A: Components<Vec2>, V: Components<Vec2> >(acc_store: &mut A, vel_store: &mut V ) {
 	
 	/* Defines a closure, taking a Entity and a mutable reference of a Vec2, which adds the acc to the vel */
 	let apply_force = | entity: Entity, vel: &mut Vec2 | {
@@ -119,7 +215,13 @@ pub fn acceleration_system< A: Store<Vec2>, V: Store<Vec2> >(acc_store: &mut A,
 	vel_store.for_each_mut( apply_force );
 }
 
-/// Detects collisions and swaps the velocity directions of the entities which collide
+/// Detects collisions and bounces the entities which collide off each other, returning a Collision for each pair resolved
+///
+/// Each bounce combines the entities' materials, or the default material for those without one, and conserves their
+/// momentum. Entities with shapes bounce along the normal of the contact between their shapes, found by the separating
+/// axis theorem, and where the shapes touch, so a contact off to one side of an entity spins it. Each pair is found from
+/// both of its entities, but only resolved once, as the first leaves them moving apart. Entities which overlap are pushed
+/// apart first, the lighter one further, so bodies resting on each other don't sink in.
 ///
 /// # Examples
 ///
@@ -127,59 +229,147 @@ pub fn acceleration_system< A: Store<Vec2>, V: Store<Vec2> >(acc_store: &mut A,
 ///
 /// ```
 ///
-pub fn collision_system< V: Store<Vec2>, P: Store<Vec2>, AT: Store<Attributes> >(vel_store: &mut V,
-																				 pos_store: &P,
-																				 atr_store: &mut AT,
-																				 regions: &mut [[HashMap<u64, Entity>; 10]; 10] ) {
+pub fn collision_system< V: Components<Vec2>, P: Components<Vec2>, AT: Components<Attributes>, M: Components<Material>, S: Components<Shape> >(vel_store: &mut V,
+																													  pos_store: &mut P,
+																													  atr_store: &mut AT,
+																													  mat_store: &M,
+																													  shp_store: &S,
+																													  regions: &mut [Vec<HashMap<u64, Entity>>] ) -> Vec<Collision> {
 	/* Create a vector to store entities which collide with each other */
 	let mut collisions = Vec::new( );
 	
-	/* Apply a closure to each entity with a pos component */
-	pos_store.for_each( | entity_a, pos_a| {
+	/* Apply a closure to each entity with a pos component, adding the entities in its region it collides with */
+	pos_store.for_each( | entity_a, pos_a| region_pairs( entity_a, pos_a, &*pos_store, &*atr_store, regions, &mut collisions ) );
+	
+	resolve_collisions( collisions, vel_store, pos_store, atr_store, mat_store, shp_store )
+}
+
+/// Adds the pairs of entity A and each entity in its region it collides with to collisions
+pub(crate) fn region_pairs< P: Components<Vec2>, AT: Components<Attributes> >(entity_a: Entity,
+																	 pos_a: &Vec2,
+																	 pos_store: &P,
+																	 atr_store: &AT,
+																	 regions: &[Vec<HashMap<u64, Entity>>],
+																	 collisions: &mut Vec<(Entity, Entity)> ) {
+	/* Get current entity A's attributes */
+	let atr_a = atr_store.get( entity_a ).unwrap();
+
+	/* Apply a closure to each entity in the same region as entity A */
+	regions[atr_a.row][atr_a.column].iter().for_each( | entity_b | {
 		
-		/* Get current entity A's attributes */
-		let atr_a = atr_store.get( entity_a ).unwrap();
+		/* Get the pos of another entity, entity B, in entity A's region */
+		let pos_b = pos_store.get( *entity_b.1 ).unwrap();
 
-		/* Apply a closure to each entity in the same region as entity A */
-		regions[atr_a.row][atr_a.column].iter().for_each( | entity_b | {
-			
-			/* Get the pos of another entity, entity B, in entity A's region */
-			let pos_b = pos_store.get( *entity_b.1 ).unwrap();
+		/* Calculate the combined radius of entity A and entity B */
+		let rad = atr_a.radius + atr_store.get( *entity_b.1 ).unwrap().radius;
+		
+		/* If the distance squared between entity A and B is equal to the combined radius squared,
+		   add entity A and B to the collisions list as a tuple */
+		if pos_a.dist_sq( pos_b ).abs() - rad.powf( 2.0 ) < 0.0001 && ( entity_b.1.id() != entity_a.id() ) {
+			collisions.push((entity_a, *entity_b.1));
+		}
+	});
+}
 
-			/* Calculate the combined radius of entity A and entity B */
-			let rad = atr_a.radius + atr_store.get( *entity_b.1 ).unwrap().radius;
-			
-			/* If the distance squared between entity A and B is equal to the combined radius squared,
-			   add entity A and B to the collisions list as a tuple */
-			if pos_a.dist_sq( pos_b ).abs() - rad.powf( 2.0 ) < 0.0001 && ( entity_b.1.id() != entity_a.id() ) {
-				collisions.push((entity_a, *entity_b.1));
-			}
-		});
+/// Pushes each pair of colliding entities apart and bounces them off each other, returning a Collision for each pair which
+/// was moving together
+///
+/// The pairs can be in any order, e.g. merged from several threads, as they are sorted before being resolved.
+pub(crate) fn resolve_collisions< V: Components<Vec2>, P: Components<Vec2>, AT: Components<Attributes>, M: Components<Material>, S: Components<Shape> >(mut collisions: Vec<(Entity, Entity)>,
+																																vel_store: &mut V,
+																																pos_store: &mut P,
+																																atr_store: &mut AT,
+																																mat_store: &M,
+																																shp_store: &S ) -> Vec<Collision> {
+	/* Sorts the collision pairs by the positions of their entities, so they are resolved in the same order whatever order
+	   the stores iterate in and the same seed gives the same simulation */
+	let key = | entity: Entity | { let pos = pos_store.get( entity ).unwrap(); ( pos.x, pos.y ) };
+	collisions.sort_by( | (a1, b1), (a2, b2) | {
+		let ( a1, b1, a2, b2 ) = ( key( *a1 ), key( *b1 ), key( *a2 ), key( *b2 ) );
+		a1.0.total_cmp( &a2.0 ).then( a1.1.total_cmp( &a2.1 ) )
+			.then( b1.0.total_cmp( &b2.0 ) ).then( b1.1.total_cmp( &b2.1 ) )
 	});
-	
+
 	/* Iterate through each collision pair tuple in the collisions vector */
-	for ( entity_a, entity_b ) in collisions {
-		
-		/* Create some copies of entity A's and entity B's velocity components */
-		let mut vel_a1 = *vel_store.get( entity_a ).unwrap();
-		let mut vel_b = *vel_store.get( entity_b ).unwrap();
-		let vel_a2 = *vel_store.get( entity_a ).unwrap();
-		
-		/* Get the attributes components for the entities, will be used to reduce velocity based on their respective masses */
-		let _atr_a = atr_store.get( entity_a ).unwrap();
-		let _atr_b = atr_store.get( entity_b ).unwrap();
-		
-		/* Swaps the angles of the velocity component of the entities */
-		vel_a1.set_theta( &vel_b.theta() );
-		vel_b.set_theta( &vel_a2.theta() );
-		
-		/* Assigns the swapped velocities back to the entities */
-		*vel_store.get_mut( entity_a ).unwrap() = vel_a1;
-		*vel_store.get_mut( entity_b ).unwrap() = vel_b;
+	let mut resolved = Vec::with_capacity( collisions.len() / 2 );
+	for &( entity_a, entity_b ) in collisions.iter() {
+		let ( mut pos_a, mut pos_b ) = ( *pos_store.get( entity_a ).unwrap(), *pos_store.get( entity_b ).unwrap() );
+		let ( vel_a, vel_b ) = ( *vel_store.get( entity_a ).unwrap(), *vel_store.get( entity_b ).unwrap() );
+		let ( atr_a, atr_b ) = ( atr_store.get( entity_a ).unwrap(), atr_store.get( entity_b ).unwrap() );
+		let ( shape_a, shape_b ) = ( shp_store.get( entity_a ), shp_store.get( entity_b ) );
+		let material = mat_store.get( entity_a ).copied().unwrap_or_default().combine( &mat_store.get( entity_b ).copied().unwrap_or_default() );
+
+		/* The normal points from entity A to entity B. Circles bounce along the line between their centres as soon as they
+		   touch, and entities exactly on top of each other have no direction to bounce in */
+		if shape_a.is_none() && shape_b.is_none() {
+			let ( dx, dy ) = ( pos_b.x - pos_a.x, pos_b.y - pos_a.y );
+			let dist = ( dx * dx + dy * dy ).sqrt();
+			if dist == 0.0 {
+				continue;
+			}
+			let normal = Vec2::new( &( dx / dist ), &( dy / dist ) );
+			separate( pos_store, ( entity_a, &mut pos_a, atr_a ), ( entity_b, &mut pos_b, atr_b ), &normal, atr_a.radius + atr_b.radius - dist );
+
+			/* Bounces B off A if they're moving together */
+			let rel_vel = Vec2::new( &( vel_b.x - vel_a.x ), &( vel_b.y - vel_a.y ) );
+			let Some( impulse ) = contact_impulse( &rel_vel, &normal, 1.0 / atr_a.mass + 1.0 / atr_b.mass, &material ) else {
+				continue;
+			};
+
+			/* B gains the impulse and A loses it, each changing velocity less the heavier it is */
+			*vel_store.get_mut( entity_a ).unwrap() = Vec2::new( &( vel_a.x - impulse.x / atr_a.mass ), &( vel_a.y - impulse.y / atr_a.mass ) );
+			*vel_store.get_mut( entity_b ).unwrap() = Vec2::new( &( vel_b.x + impulse.x / atr_b.mass ), &( vel_b.y + impulse.y / atr_b.mass ) );
+
+			/* The impulse on entity A is its change in momentum */
+			resolved.push( Collision { a: entity_a, b: entity_b, impulse: Vec2::new( &-impulse.x, &-impulse.y ) } );
+			continue;
+		}
+
+		/* Entities with shapes may be near enough for their circles to touch without their shapes touching. Where they do
+		   touch, B bounces off A at the middle of the contact points, which spins either of them the contact is off centre of */
+		let ( circle_a, circle_b ) = ( Shape::Circle { radius: atr_a.radius }, Shape::Circle { radius: atr_b.radius } );
+		let ( shape_a, shape_b ) = ( shape_a.unwrap_or( &circle_a ), shape_b.unwrap_or( &circle_b ) );
+		let Some( manifold ) = collide( shape_a, &pos_a, atr_a.angle, shape_b, &pos_b, atr_b.angle ) else {
+			continue;
+		};
+		let point = manifold.centre().unwrap_or_else( || Vec2::new( &( ( pos_a.x + pos_b.x ) / 2.0 ), &( ( pos_a.y + pos_b.y ) / 2.0 ) ) );
+
+		/* The contact points lie between the two surfaces, so move by the average of how far each entity is pushed */
+		let ( before_a, before_b ) = ( pos_a, pos_b );
+		separate( pos_store, ( entity_a, &mut pos_a, atr_a ), ( entity_b, &mut pos_b, atr_b ), &manifold.normal, manifold.depth );
+		let point = Vec2::new( &( point.x + ( pos_a.x - before_a.x + pos_b.x - before_b.x ) / 2.0 ),
+							   &( point.y + ( pos_a.y - before_a.y + pos_b.y - before_b.y ) / 2.0 ) );
+		let ( mut body_a, mut body_b ) = ( motion( &pos_a, &vel_a, atr_a, Some( shape_a ) ), motion( &pos_b, &vel_b, atr_b, Some( shape_b ) ) );
+		let Some( impulse ) = rigid_impulse( &mut body_a, &mut body_b, &point, &manifold.normal, &material ) else {
+			continue;
+		};
+
+		*vel_store.get_mut( entity_a ).unwrap() = body_a.vel;
+		*vel_store.get_mut( entity_b ).unwrap() = body_b.vel;
+		atr_store.get_mut( entity_a ).unwrap().spin = body_a.spin;
+		atr_store.get_mut( entity_b ).unwrap().spin = body_b.spin;
+		resolved.push( Collision { a: entity_a, b: entity_b, impulse: Vec2::new( &-impulse.x, &-impulse.y ) } );
+	}
+	resolved
+}
+
+/// Pushes entities A and B, overlapping by depth, apart along the normal from A to B, each by its share of their inverse
+/// mass, updating both their copies and the pos store
+fn separate< P: Components<Vec2> >( pos_store: &mut P, a: ( Entity, &mut Vec2, &Attributes ), b: ( Entity, &mut Vec2, &Attributes ), normal: &Vec2, depth: f32 ) {
+	if depth <= 0.0 {
+		return;
 	}
+	let ( inv_a, inv_b ) = ( 1.0 / a.2.mass, 1.0 / b.2.mass );
+	let ( share_a, share_b ) = ( depth * inv_a / ( inv_a + inv_b ), depth * inv_b / ( inv_a + inv_b ) );
+	a.1.x -= normal.x * share_a;
+	a.1.y -= normal.y * share_a;
+	b.1.x += normal.x * share_b;
+	b.1.y += normal.y * share_b;
+	*pos_store.get_mut( a.0 ).unwrap() = *a.1;
+	*pos_store.get_mut( b.0 ).unwrap() = *b.1;
 }
 
-/// Draws circles at the position component of the entities
+/// Draws circles at the position component of the entities, or their shapes turned by their angle if they have one
 ///
 /// # Examples
 ///
@@ -187,30 +377,35 @@ pub fn collision_system< V: Store<Vec2>, P: Store<Vec2>, AT: Store<Attributes> >
 ///
 /// ```
 ///
-pub fn render_system< P: Store<Vec2>, A: Store<Attributes> >(display: &mut RaylibDrawHandle,
-															 screen_size: (i32, i32),
-															 pos_store: &P,
-															 atr_store: &A ) {
-	/* Converts screen_size tuple to f32, for use in calculations */
-	let screen_size = ( screen_size.0 as f32, screen_size.1 as f32 );
-	
+pub fn render_system< C: Canvas + ?Sized, P: Components<Vec2>, A: Components<Attributes>, S: Components<Shape> >(canvas: &mut C,
+																						  pos_store: &P,
+																						  atr_store: &A,
+																						  shp_store: &S ) {
 	/* Applies a closure for each entity with a position */
 	pos_store.for_each( | entity, pos| {
 		
 		/* If the current entity has a attributes component, assign it to atr and run the following code block */
 		if let Some( atr ) = atr_store.get( entity ) {
-			
-			/* Maps the y value, from the range 0->screen_height, to the range screen_height->0, so that the coordinate (0,0) is the bottom left
-			   instead of the top left */
-			let y = map( pos.y, 0.0..screen_size.1, screen_size.1..0.0 );
-			
-			/* Draws a circle at the entities position, with the entities radius and color */
-			display.draw_circle(pos.x as i32, y as i32, atr.radius, atr.color );
+			match shp_store.get( entity ) {
+				/* Draws a circle at the entities position, flipped so that the coordinate (0,0) is the bottom left instead of the top left,
+				   with the entities radius and color */
+				None => canvas.circle( canvas.flip( pos ), atr.radius, atr.color ),
+				/* Circle shapes also have a line from their centre to their edge, so they can be seen turning */
+				Some( Shape::Circle { radius } ) => {
+					let edge = Vec2::new( &( pos.x + radius * atr.angle.cos() ), &( pos.y + radius * atr.angle.sin() ) );
+					canvas.circle( canvas.flip( pos ), *radius, atr.color );
+					canvas.line( canvas.flip( pos ), canvas.flip( &edge ), 2.0, Rgba::new( 0, 0, 0, 120 ) );
+				}
+				Some( shape ) => {
+					let points: Vec<Vec2> = shape.points( pos, atr.angle ).iter().map( |point| canvas.flip( point ) ).collect();
+					canvas.polygon( &points, atr.color );
+				}
+			}
 		}
 	});
 }
 
-/// Drops entities which go out of bounds
+/// Draws the obstacles in their shapes, with segments as lines
 ///
 /// # Examples
 ///
@@ -218,40 +413,67 @@ pub fn render_system< P: Store<Vec2>, A: Store<Attributes> >(display: &mut Rayli
 ///
 /// ```
 ///
-pub fn drop_system< AT: Store<Attributes>, P: Store<Vec2>, V: Store<Vec2>, A: Store<Vec2> >(screen_size: (i32, i32),
-																							entity_manager: &mut EntityManager,
-																							acc_store: &mut A,
-																							vel_store: &mut V,
-																							pos_store: &mut P,
-																							atr_store: &mut AT ) {
-	/* Creates a vector of entities which will be dropped */
-	let mut to_drop = Vec::new( );
-	
-	/* Converts screen_size tuple to f32, for use in calculations */
-	let screen_size = ( screen_size.0 as f32, screen_size.1 as f32 );
-	
-	/* Checks each entity to see if it's position is out of bounds. If it is, the entity is added to the to_drop vector */
-	pos_store.for_each( | entity, pos | {
-		if pos.x > screen_size.0 + 2.0 || pos.x < -2.0 || pos.y > screen_size.1 + 2.0 || pos.y < -2.0 {
-			to_drop.push( entity );
+pub fn obstacle_render_system< C: Canvas + ?Sized >( canvas: &mut C, obstacles: &[Obstacle] ) {
+	for obstacle in obstacles {
+		match &obstacle.collider {
+			Collider::Circle { pos, radius } => canvas.circle( canvas.flip( pos ), *radius, obstacle.color ),
+			Collider::Segment { from, to } => canvas.line( canvas.flip( from ), canvas.flip( to ), 3.0, obstacle.color ),
+			collider => {
+				let points: Vec<Vec2> = collider.points().iter().map( |point| canvas.flip( point ) ).collect();
+				canvas.polygon( &points, obstacle.color );
+			}
+		}
+	}
+}
+
+/// Rebuilds the regions from the row and column in each entity's attributes, e.g. after the stores have been restored
+///
+/// Only entities with a velocity which have been placed in a region by the movement system are added, as they are the only
+/// ones the movement system would have added.
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn region_system< V: Components<Vec2>, A: Components<Attributes> >(vel_store: &V,
+															atr_store: &A,
+															regions: &mut [Vec<HashMap<u64, Entity>>] ) {
+	regions.iter_mut().flatten().for_each( | region | region.clear() );
+
+	atr_store.for_each( | entity, atr | {
+		/* The movement system clamps rows and columns to at least 1, so 0 means the entity hasn't been placed yet */
+		if vel_store.get( entity ).is_some() && atr.row > 0 && atr.column > 0 {
+			if let Some( region ) = regions.get_mut( atr.row ).and_then( | row | row.get_mut( atr.column ) ) {
+				region.insert( entity.id(), entity );
+			}
 		}
 	});
+}
+
+/// Finds entities which have come to rest on the bottom edge, returning the Despawned events of the entities to despawn.
+/// Entities leaving the screen are despawned by the boundary instead
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn drop_system< AT: Components<Attributes>, P: Components<Vec2>, V: Components<Vec2>, S: Components<Shape> >(vel_store: &V,
+																							 pos_store: &P,
+																							 atr_store: &AT,
+																							 shp_store: &S ) -> Vec<Despawned> {
+	/* Creates a vector of entities which will be dropped */
+	let mut to_drop = Vec::new( );
 	
 	/* Checks if they are resting on the bottom edge of screen, if they are adds them to the drop list */
-	vel_store.for_each( | entity, vel | {
-		let pos = pos_store.get( entity ).unwrap();
-		let atr = atr_store.get( entity ).unwrap();
-		if vel.x < 0.38 && vel.y < 0.38 && pos.y - atr.radius < 0.40 {
-			to_drop.push(entity);
+	query( ( read( vel_store ), read( pos_store ), read( atr_store ), optional( read( shp_store ) ) ) ).for_each( | entity, ( vel, pos, atr, shape ) | {
+		if vel.x < 0.38 && vel.y < 0.38 && pos.y - reach( atr, shape )( Side::Bottom ) < 0.40 {
+			to_drop.push( Despawned { entity, reason: DespawnReason::AtRest } );
 		}
 	});
 
-	/* For each entity in the to_drop vector, drop it from the entity manager and each components store */
-	for mut td in to_drop {
-		entity_manager.drop( &mut td );
-		pos_store.drop( td );
-		vel_store.drop( td );
-		acc_store.drop( td );
-		atr_store.drop( td );
-	}
-}
\ No newline at end of file
+	to_drop
+}
@@ -0,0 +1,241 @@
+//! What happens to entities at the edges of the screen. A Boundary resource gives each edge a mode: a solid wall they
+//! bounce off, wrapping around to the opposite edge, despawning them once they've left, letting them pass through, or a
+//! custom function.
+//!
+//! Boundary systems return a Crossing for each thing which happened at an edge, and send_crossings turns them into
+//! WallHit, Wrapped and Despawned events, despawning the entities which left through a despawn edge.
+
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::events::*;
+use crate::material::*;
+use crate::shape::Shape;
+use crate::world::World;
+
+/// A custom edge, called for each entity touching or past the edge with the side, the screen size, the entity's radius,
+/// position and velocity. Returns what happened, if anything
+pub type EdgeFn = fn( Side, (f32, f32), f32, &mut Vec2, &mut Vec2 ) -> Option<Crossed>;
+
+/// How an edge of the screen treats entities reaching it
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Edge {
+	/// A solid wall of the material, which entities bounce off
+	Bounce( Material ),
+	/// Entities whose centre crosses the edge come back through the opposite edge
+	Wrap,
+	/// Entities which have completely left through the edge are despawned
+	Despawn,
+	/// Entities pass through the edge, and carry on off the screen
+	Open,
+	/// Calls the function. Custom edges are set in code, so can't be saved in or loaded from a scene file
+	#[serde(skip)]
+	Custom( EdgeFn ),
+}
+
+impl PartialEq for Edge {
+	fn eq( &self, other: &Edge ) -> bool {
+		match ( self, other ) {
+			( Edge::Bounce( a ), Edge::Bounce( b ) ) => a == b,
+			( Edge::Wrap, Edge::Wrap ) | ( Edge::Despawn, Edge::Despawn ) | ( Edge::Open, Edge::Open ) => true,
+			( Edge::Custom( a ), Edge::Custom( b ) ) => std::ptr::fn_addr_eq( *a, *b ),
+			_ => false,
+		}
+	}
+}
+
+/// What happened to an entity at an edge
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Crossed {
+	/// It bounced off the edge
+	Bounced,
+	/// It wrapped around to the opposite edge
+	Wrapped,
+	/// It left through the edge, and should be despawned
+	Despawn,
+}
+
+/// Something which happened to an entity at an edge
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Crossing {
+	pub entity: Entity,
+	pub side: Side,
+	pub crossed: Crossed,
+}
+
+/// The mode of each edge of the screen
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::boundary::*;
+/// use rusty_nature_of_code::events::Side;
+/// use rusty_nature_of_code::material::Material;
+///
+/// /* A torus, apart from a floor to bounce off */
+/// let boundary = Boundary { bottom: Edge::Bounce( Material::ELASTIC ), ..Boundary::torus() };
+///
+/// let ( mut pos, mut vel ) = ( Vec2::new( &-1.0, &5.0 ), Vec2::new( &-2.0, &-3.0 ) );
+/// let mut crossed = Vec::new();
+/// boundary.apply( ( 100.0, 100.0 ), 5.0, &Material::ELASTIC, &mut pos, &mut vel, |side, what| crossed.push( ( side, what ) ) );
+///
+/// assert_eq!( crossed, [ ( Side::Left, Crossed::Wrapped ), ( Side::Bottom, Crossed::Bounced ) ] );
+/// assert_eq!( pos, Vec2::new( &99.0, &5.0 ) );
+/// assert_eq!( vel, Vec2::new( &-2.0, &3.0 ) );
+/// ```
+///
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Boundary {
+	pub left: Edge,
+	pub right: Edge,
+	pub bottom: Edge,
+	pub top: Edge,
+}
+
+impl Default for Boundary {
+	/// Walls which keep the bounce of what hits them, with a little friction on the sides and ceiling, and more on the floor
+	fn default() -> Self {
+		let wall = Edge::Bounce( Material::WALL );
+		Boundary { left: wall, right: wall, bottom: Edge::Bounce( Material { restitution: 1.0, static_friction: 0.4, dynamic_friction: 0.3 } ), top: wall }
+	}
+}
+
+impl Boundary {
+	/// Every edge in the same mode
+	pub fn all( edge: Edge ) -> Boundary {
+		Boundary { left: edge, right: edge, bottom: edge, top: edge }
+	}
+
+	/// Every edge wraps around to the opposite one
+	pub fn torus() -> Boundary {
+		Boundary::all( Edge::Wrap )
+	}
+
+	/// The mode of the edge on side
+	pub fn edge( &self, side: Side ) -> &Edge {
+		match side {
+			Side::Left => &self.left,
+			Side::Right => &self.right,
+			Side::Bottom => &self.bottom,
+			Side::Top => &self.top,
+		}
+	}
+
+	/// Whether the material of every solid edge is valid
+	pub fn is_valid( &self ) -> bool {
+		[ self.left, self.right, self.bottom, self.top ].iter().all( |edge| match edge {
+			Edge::Bounce( material ) => material.is_valid(),
+			_ => true,
+		})
+	}
+
+	/// Applies each edge to a circle of radius, with material, on a screen of size, calling crossed with the side and what
+	/// happened for each edge which did something
+	pub fn apply( &self, size: (f32, f32), radius: f32, material: &Material, pos: &mut Vec2, vel: &mut Vec2, crossed: impl FnMut( Side, Crossed ) ) {
+		/* Walls don't move, so the circle's mass cancels out and an inverse mass of 1.0 gives its change in velocity */
+		self.each_edge( size, |_| radius, pos, vel, |_, wall, _, normal, vel| {
+			contact_impulse( vel, normal, 1.0, &material.combine( wall ) ).map( |impulse| vel.add( &impulse ) ).is_some()
+		}, crossed );
+	}
+
+	/// Applies each edge to a body of shape turned by angle, like apply does for circles. The body sits flat against solid
+	/// edges, and bounces off them where it touches, so a body landing on a corner is spun as well as bounced
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use miscmath::prelude::*;
+	/// use rusty_nature_of_code::boundary::*;
+	/// use rusty_nature_of_code::material::*;
+	/// use rusty_nature_of_code::shape::Shape;
+	///
+	/// /* A box turned 30 degrees falls onto the floor on its lowest corner, left of its centre. Some of the bounce goes into
+	/// tipping it clockwise, so it's slowed rather than sent back up */
+	/// let shape = Shape::Rect { width: 20.0, height: 10.0 };
+	/// let mut body = Motion { pos: Vec2::new( &50.0, &5.0 ), vel: Vec2::new( &0.0, &-3.0 ), spin: 0.0, inv_mass: 1.0, inv_inertia: 1.0 / shape.inertia( 1.0 ) };
+	/// Boundary::default().apply_body( ( 100.0, 100.0 ), &shape, 30f32.to_radians(), &Material::default(), &mut body, |_, _| () );
+	///
+	/// assert!( body.vel.y > -3.0 && body.spin < 0.0 );
+	/// assert!( ( body.pos.y - shape.extent( 30f32.to_radians(), &Vec2::new( &0.0, &-1.0 ) ) ).abs() < 0.0001 );
+	/// ```
+	///
+	pub fn apply_body( &self, size: (f32, f32), shape: &Shape, angle: f32, material: &Material, body: &mut Motion, crossed: impl FnMut( Side, Crossed ) ) {
+		let Motion { pos, vel, spin, inv_mass, inv_inertia } = body;
+		let extent = |side: Side| shape.extent( angle, &side.outward() );
+		self.each_edge( size, extent, pos, vel, |side, wall, pos, normal, vel| {
+			let mut moving = Motion { pos: *pos, vel: *vel, spin: *spin, inv_mass: *inv_mass, inv_inertia: *inv_inertia };
+			let point = shape.support( pos, angle, &side.outward() );
+			let bounced = rigid_impulse( &mut Motion::fixed( point ), &mut moving, &point, normal, &material.combine( wall ) ).is_some();
+			( *vel, *spin ) = ( moving.vel, moving.spin );
+			bounced
+		}, crossed );
+	}
+
+	/// Applies each edge to a body which reaches extent( side ) from its position towards each side. Solid edges push the
+	/// body back inside them, then call bounce with the side, the edge's material, the body's position, the normal pointing
+	/// away from the edge and the body's velocity, if it's touching the edge. bounce returns whether the body bounced
+	fn each_edge( &self, size: (f32, f32), extent: impl Fn( Side ) -> f32, pos: &mut Vec2, vel: &mut Vec2,
+				  mut bounce: impl FnMut( Side, &Material, &Vec2, &Vec2, &mut Vec2 ) -> bool, mut crossed: impl FnMut( Side, Crossed ) ) {
+		for side in [ Side::Left, Side::Bottom, Side::Right, Side::Top ] {
+			let radius = extent( side );
+
+			/* How far the body's centre is past the edge, negative while it's inside */
+			let past = match side {
+				Side::Left => -pos.x,
+				Side::Right => pos.x - size.0,
+				Side::Bottom => -pos.y,
+				Side::Top => pos.y - size.1,
+			};
+
+			let what = match self.edge( side ) {
+				Edge::Bounce( wall ) => {
+					/* Constrains the body to inside the edge, factoring in its size, then bounces it off if it's touching
+					   and moving into it */
+					match side {
+						Side::Left => pos.x = pos.x.max( radius ),
+						Side::Right => pos.x = pos.x.min( size.0 - radius ),
+						Side::Bottom => pos.y = pos.y.max( radius ),
+						Side::Top => pos.y = pos.y.min( size.1 - radius ),
+					}
+					let outward = side.outward();
+					let normal = Vec2::new( &-outward.x, &-outward.y );
+					( past > -radius - 0.0001 && bounce( side, wall, pos, &normal, vel ) ).then_some( Crossed::Bounced )
+				}
+				Edge::Wrap => ( past > 0.0 ).then( || {
+					match side {
+						Side::Left => pos.x += size.0,
+						Side::Right => pos.x -= size.0,
+						Side::Bottom => pos.y += size.1,
+						Side::Top => pos.y -= size.1,
+					}
+					Crossed::Wrapped
+				}),
+				Edge::Despawn => ( past > radius ).then_some( Crossed::Despawn ),
+				Edge::Open => None,
+				Edge::Custom( edge ) => ( past > -radius ).then( || edge( side, size, radius, pos, vel ) ).flatten(),
+			};
+			if let Some( what ) = what {
+				crossed( side, what );
+			}
+		}
+	}
+}
+
+/// Sends a WallHit for each bounce and a Wrapped for each wrap, and despawns the entities which left through a despawn
+/// edge, sending a Despawned for each. The world must have all three events
+pub fn send_crossings( world: &World, crossings: impl IntoIterator<Item = Crossing> ) {
+	for Crossing { entity, side, crossed } in crossings {
+		match crossed {
+			Crossed::Bounced => world.send( WallHit { entity, side } ),
+			Crossed::Wrapped => world.send( Wrapped { entity, side } ),
+			/* An entity can leave through two edges at once, at a corner, but is only despawned once */
+			Crossed::Despawn => if world.contains( entity ) {
+				world.despawn( entity );
+				world.send( Despawned { entity, reason: DespawnReason::OutOfBounds } );
+			},
+		}
+	}
+}
@@ -0,0 +1,60 @@
+//! Drawing through a Canvas, so systems don't depend on a particular renderer. Sketches can be drawn to a raylib window,
+//! recorded for tests, rasterised in software, or written to an svg file.
+
+use miscmath::prelude::*;
+use crate::color::Rgba;
+
+pub mod recording;
+pub mod raster;
+pub mod svg;
+#[cfg(feature = "render")]
+pub mod raylib_canvas;
+
+pub use recording::{DrawCommand, RecordingCanvas};
+pub use raster::RasterCanvas;
+pub use svg::SvgCanvas;
+
+/// Something which can be drawn on. Positions are in screen coordinates, with (0,0) the top left
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::canvas::*;
+/// use rusty_nature_of_code::color::Rgba;
+///
+/// let mut canvas = RecordingCanvas::new( 640.0, 480.0 );
+/// let pos = canvas.flip( &Vec2::new( &10.0, &0.0 ) );
+/// canvas.circle( pos, 5.0, Rgba::WHITE );
+///
+/// assert_eq!( canvas.commands(), &[DrawCommand::Circle { center: Vec2::new( &10.0, &480.0 ), radius: 5.0, color: Rgba::WHITE }] );
+/// ```
+///
+pub trait Canvas {
+	/// Width and height of the canvas in pixels
+	fn size( &self ) -> ( f32, f32 );
+
+	/// Fills the whole canvas with color
+	fn clear( &mut self, color: Rgba );
+
+	/// Draws a filled circle
+	fn circle( &mut self, center: Vec2, radius: f32, color: Rgba );
+
+	/// Draws a line thickness pixels wide
+	fn line( &mut self, from: Vec2, to: Vec2, thickness: f32, color: Rgba );
+
+	/// Draws a filled rectangle with its top left corner at pos
+	fn rect( &mut self, pos: Vec2, size: Vec2, color: Rgba );
+
+	/// Draws a filled convex polygon, with its points in either order around it
+	fn polygon( &mut self, points: &[Vec2], color: Rgba );
+
+	/// Draws text with its top left corner at pos, size pixels tall
+	fn text( &mut self, text: &str, pos: Vec2, size: f32, color: Rgba );
+
+	/// Maps a position from world coordinates, where (0,0) is the bottom left, to screen coordinates, where (0,0) is the top left
+	fn flip( &self, pos: &Vec2 ) -> Vec2 {
+		let height = self.size().1;
+		Vec2::new( &pos.x, &map( pos.y, 0.0..height, height..0.0 ) )
+	}
+}
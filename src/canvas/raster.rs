@@ -0,0 +1,241 @@
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+
+/// Width and height in font pixels of each glyph in FONT
+const GLYPH_SIZE: ( usize, usize ) = ( 5, 7 );
+
+/// A 5x7 bitmap font covering digits, letters and the punctuation the sketches use. Each row is 5 bits, with the leftmost pixel
+/// in the highest bit. Lower case letters are drawn with their upper case glyph
+const FONT: [( char, [u8; 7] ); 50] = [
+	( ' ', [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00] ),
+	( '0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E] ),
+	( '1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E] ),
+	( '2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F] ),
+	( '3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E] ),
+	( '4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02] ),
+	( '5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E] ),
+	( '6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E] ),
+	( '7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08] ),
+	( '8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E] ),
+	( '9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C] ),
+	( 'A', [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11] ),
+	( 'B', [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E] ),
+	( 'C', [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E] ),
+	( 'D', [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C] ),
+	( 'E', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F] ),
+	( 'F', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10] ),
+	( 'G', [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F] ),
+	( 'H', [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11] ),
+	( 'I', [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E] ),
+	( 'J', [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C] ),
+	( 'K', [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11] ),
+	( 'L', [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F] ),
+	( 'M', [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11] ),
+	( 'N', [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11] ),
+	( 'O', [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E] ),
+	( 'P', [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10] ),
+	( 'Q', [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D] ),
+	( 'R', [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11] ),
+	( 'S', [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E] ),
+	( 'T', [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04] ),
+	( 'U', [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E] ),
+	( 'V', [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04] ),
+	( 'W', [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A] ),
+	( 'X', [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11] ),
+	( 'Y', [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04] ),
+	( 'Z', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F] ),
+	( '=', [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00] ),
+	( '.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C] ),
+	( ',', [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08] ),
+	( ':', [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00] ),
+	( '-', [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00] ),
+	( '+', [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00] ),
+	( '_', [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F] ),
+	( '/', [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00] ),
+	( '(', [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02] ),
+	( ')', [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08] ),
+	( '%', [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03] ),
+	( '!', [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04] ),
+	( '?', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04] ),
+];
+
+/// Looks up the glyph for c, falling back to '?' for characters the font doesn't have
+fn glyph( c: char ) -> &'static [u8; 7] {
+	let c = c.to_ascii_uppercase();
+	FONT.iter().find( |( g, _ )| *g == c )
+		.or_else( || FONT.iter().find( |( g, _ )| *g == '?' ) )
+		.map( |( _, rows )| rows )
+		.unwrap()
+}
+
+/// A software rasteriser drawing into an rgba buffer, so sketches can be drawn without a display or gpu
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::canvas::*;
+/// use rusty_nature_of_code::color::Rgba;
+///
+/// let mut canvas = RasterCanvas::new( 64, 64 );
+/// canvas.clear( Rgba::BLACK );
+/// canvas.circle( Vec2::new( &32.0, &32.0 ), 10.0, Rgba::WHITE );
+///
+/// assert_eq!( canvas.pixel( 32, 32 ), Rgba::WHITE );
+/// assert_eq!( canvas.pixel( 0, 0 ), Rgba::BLACK );
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct RasterCanvas {
+	width: u32,
+	height: u32,
+	pixels: Vec<u8>,
+}
+
+impl RasterCanvas {
+	/// Creates a fully transparent canvas of the given size
+	pub fn new( width: u32, height: u32 ) -> RasterCanvas {
+		RasterCanvas { width, height, pixels: vec![0; width as usize * height as usize * 4] }
+	}
+
+	/// Width of the canvas in pixels
+	pub fn width( &self ) -> u32 {
+		self.width
+	}
+
+	/// Height of the canvas in pixels
+	pub fn height( &self ) -> u32 {
+		self.height
+	}
+
+	/// The rgba bytes of the canvas, row by row from the top left
+	pub fn pixels( &self ) -> &[u8] {
+		&self.pixels
+	}
+
+	/// The colour of the pixel at (x,y)
+	///
+	/// # Panics
+	///
+	/// If (x,y) is outside the canvas
+	pub fn pixel( &self, x: u32, y: u32 ) -> Rgba {
+		assert!( x < self.width && y < self.height, "pixel ({}, {}) is outside the {}x{} canvas", x, y, self.width, self.height );
+		let i = ( y as usize * self.width as usize + x as usize ) * 4;
+		Rgba::new( self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3] )
+	}
+
+	/// Blends color over the pixel at (x,y), with coverage being how much of the pixel the shape covers from 0 to 1
+	fn blend( &mut self, x: i64, y: i64, color: Rgba, coverage: f32 ) {
+		if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 || coverage <= 0.0 {
+			return;
+		}
+		let i = ( y as usize * self.width as usize + x as usize ) * 4;
+		let alpha = ( color.a as f32 / 255.0 ) * coverage.min( 1.0 );
+		let dst = &mut self.pixels[i..i + 4];
+		for ( channel, src ) in dst.iter_mut().zip( [color.r, color.g, color.b] ) {
+			*channel = ( src as f32 * alpha + *channel as f32 * ( 1.0 - alpha ) ).round() as u8;
+		}
+		dst[3] = ( 255.0 * alpha + dst[3] as f32 * ( 1.0 - alpha ) ).round() as u8;
+	}
+
+	/// Calls func with every pixel in the box from min to max that is on the canvas, and the position of the pixel's centre
+	fn for_each_pixel<F: FnMut( &mut Self, i64, i64, Vec2 )>( &mut self, min: ( f32, f32 ), max: ( f32, f32 ), mut func: F ) {
+		let x0 = ( min.0.floor() as i64 ).max( 0 );
+		let y0 = ( min.1.floor() as i64 ).max( 0 );
+		let x1 = ( max.0.ceil() as i64 ).min( self.width as i64 - 1 );
+		let y1 = ( max.1.ceil() as i64 ).min( self.height as i64 - 1 );
+		for y in y0..=y1 {
+			for x in x0..=x1 {
+				func( self, x, y, Vec2::new( &( x as f32 + 0.5 ), &( y as f32 + 0.5 ) ) );
+			}
+		}
+	}
+}
+
+impl Canvas for RasterCanvas {
+	fn size( &self ) -> ( f32, f32 ) {
+		( self.width as f32, self.height as f32 )
+	}
+
+	fn clear( &mut self, color: Rgba ) {
+		for pixel in self.pixels.chunks_exact_mut( 4 ) {
+			pixel.copy_from_slice( &[color.r, color.g, color.b, color.a] );
+		}
+	}
+
+	fn circle( &mut self, center: Vec2, radius: f32, color: Rgba ) {
+		/* Pixels within half a pixel of the edge are partly covered, which smooths the edge */
+		let min = ( center.x - radius - 1.0, center.y - radius - 1.0 );
+		let max = ( center.x + radius + 1.0, center.y + radius + 1.0 );
+		self.for_each_pixel( min, max, |canvas, x, y, p| {
+			let coverage = ( radius + 0.5 - p.dist_sq( &center ).sqrt() ).clamp( 0.0, 1.0 );
+			canvas.blend( x, y, color, coverage );
+		});
+	}
+
+	fn line( &mut self, from: Vec2, to: Vec2, thickness: f32, color: Rgba ) {
+		let half = thickness.max( 1.0 ) / 2.0;
+		let min = ( from.x.min( to.x ) - half - 1.0, from.y.min( to.y ) - half - 1.0 );
+		let max = ( from.x.max( to.x ) + half + 1.0, from.y.max( to.y ) + half + 1.0 );
+		let ( dx, dy ) = ( to.x - from.x, to.y - from.y );
+		let len_sq = dx * dx + dy * dy;
+		self.for_each_pixel( min, max, |canvas, x, y, p| {
+			/* Finds the closest point on the line to the pixel, then covers the pixel by how far inside the line's thickness it is */
+			let t = if len_sq > 0.0 { ( ( ( p.x - from.x ) * dx + ( p.y - from.y ) * dy ) / len_sq ).clamp( 0.0, 1.0 ) } else { 0.0 };
+			let closest = Vec2::new( &( from.x + dx * t ), &( from.y + dy * t ) );
+			let coverage = ( half + 0.5 - p.dist_sq( &closest ).sqrt() ).clamp( 0.0, 1.0 );
+			canvas.blend( x, y, color, coverage );
+		});
+	}
+
+	fn rect( &mut self, pos: Vec2, size: Vec2, color: Rgba ) {
+		let ( x0, y0 ) = ( pos.x.min( pos.x + size.x ), pos.y.min( pos.y + size.y ) );
+		let ( x1, y1 ) = ( pos.x.max( pos.x + size.x ), pos.y.max( pos.y + size.y ) );
+		self.for_each_pixel( ( x0, y0 ), ( x1, y1 ), |canvas, x, y, _| {
+			/* Covers each pixel by how much of it overlaps the rectangle, so edges between pixels are blended */
+			let cover_x = ( ( x + 1 ) as f32 ).min( x1 ) - ( x as f32 ).max( x0 );
+			let cover_y = ( ( y + 1 ) as f32 ).min( y1 ) - ( y as f32 ).max( y0 );
+			canvas.blend( x, y, color, cover_x.max( 0.0 ) * cover_y.max( 0.0 ) );
+		});
+	}
+
+	fn polygon( &mut self, points: &[Vec2], color: Rgba ) {
+		if points.len() < 3 {
+			return;
+		}
+		let min = points.iter().fold( ( f32::MAX, f32::MAX ), |min, p| ( min.0.min( p.x - 1.0 ), min.1.min( p.y - 1.0 ) ) );
+		let max = points.iter().fold( ( f32::MIN, f32::MIN ), |max, p| ( max.0.max( p.x + 1.0 ), max.1.max( p.y + 1.0 ) ) );
+
+		/* The sign of the area says which way round the points go, so each edge's normal can be made to point outwards */
+		let area = points.iter().zip( points.iter().cycle().skip( 1 ) ).map( |( a, b )| a.x * b.y - b.x * a.y ).sum::<f32>();
+		let turn = if area < 0.0 { -1.0 } else { 1.0 };
+		self.for_each_pixel( min, max, |canvas, x, y, p| {
+			/* A pixel's distance outside a convex polygon is the furthest it is outside any edge, which covers it the same way
+			   as the edge of a circle */
+			let outside = points.iter().zip( points.iter().cycle().skip( 1 ) ).map( |( a, b )| {
+				let ( dx, dy ) = ( b.x - a.x, b.y - a.y );
+				let len = ( dx * dx + dy * dy ).sqrt().max( f32::EPSILON );
+				( ( p.x - a.x ) * dy - ( p.y - a.y ) * dx ) / len * turn
+			}).fold( f32::MIN, f32::max );
+			canvas.blend( x, y, color, ( 0.5 - outside ).clamp( 0.0, 1.0 ) );
+		});
+	}
+
+	fn text( &mut self, text: &str, pos: Vec2, size: f32, color: Rgba ) {
+		/* Each font pixel is scaled up so the glyph and a pixel of spacing below it are size pixels tall */
+		let scale = ( size / ( GLYPH_SIZE.1 + 1 ) as f32 ).max( 1.0 );
+		let mut x = pos.x;
+		for c in text.chars() {
+			for ( row, bits ) in glyph( c ).iter().enumerate() {
+				for column in 0..GLYPH_SIZE.0 {
+					if bits & ( 1 << ( GLYPH_SIZE.0 - 1 - column ) ) != 0 {
+						let corner = Vec2::new( &( x + column as f32 * scale ), &( pos.y + row as f32 * scale ) );
+						self.rect( corner, Vec2::new( &scale, &scale ), color );
+					}
+				}
+			}
+			x += ( GLYPH_SIZE.0 + 1 ) as f32 * scale;
+		}
+	}
+}
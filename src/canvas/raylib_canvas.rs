@@ -0,0 +1,41 @@
+use miscmath::prelude::*;
+use raylib::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+
+/// Draws to the raylib window
+impl Canvas for RaylibDrawHandle<'_> {
+	fn size( &self ) -> ( f32, f32 ) {
+		( self.get_screen_width() as f32, self.get_screen_height() as f32 )
+	}
+
+	fn clear( &mut self, color: Rgba ) {
+		self.clear_background( Color::from( color ) );
+	}
+
+	fn circle( &mut self, center: Vec2, radius: f32, color: Rgba ) {
+		self.draw_circle( center.x as i32, center.y as i32, radius, Color::from( color ) );
+	}
+
+	fn line( &mut self, from: Vec2, to: Vec2, thickness: f32, color: Rgba ) {
+		self.draw_line_ex( Vector2::new( from.x, from.y ), Vector2::new( to.x, to.y ), thickness, Color::from( color ) );
+	}
+
+	fn rect( &mut self, pos: Vec2, size: Vec2, color: Rgba ) {
+		self.draw_rectangle_v( Vector2::new( pos.x, pos.y ), Vector2::new( size.x, size.y ), Color::from( color ) );
+	}
+
+	fn polygon( &mut self, points: &[Vec2], color: Rgba ) {
+		/* Raylib only fills triangle fans wound anticlockwise on screen, which is a negative area with y pointing down */
+		let mut points: Vec<Vector2> = points.iter().map( |p| Vector2::new( p.x, p.y ) ).collect();
+		let area = points.iter().zip( points.iter().cycle().skip( 1 ) ).map( |( a, b )| a.x * b.y - b.x * a.y ).sum::<f32>();
+		if area > 0.0 {
+			points.reverse();
+		}
+		self.draw_triangle_fan( &points, Color::from( color ) );
+	}
+
+	fn text( &mut self, text: &str, pos: Vec2, size: f32, color: Rgba ) {
+		self.draw_text( text, pos.x as i32, pos.y as i32, size as i32, Color::from( color ) );
+	}
+}
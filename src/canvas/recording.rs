@@ -0,0 +1,88 @@
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+
+/// A single call made on a RecordingCanvas
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+	/// A call to clear
+	Clear( Rgba ),
+	/// A call to circle
+	Circle { center: Vec2, radius: f32, color: Rgba },
+	/// A call to line
+	Line { from: Vec2, to: Vec2, thickness: f32, color: Rgba },
+	/// A call to rect
+	Rect { pos: Vec2, size: Vec2, color: Rgba },
+	/// A call to polygon
+	Polygon { points: Vec<Vec2>, color: Rgba },
+	/// A call to text
+	Text { text: String, pos: Vec2, size: f32, color: Rgba },
+}
+
+/// A canvas which records what is drawn on it instead of drawing it, so draw output can be checked in tests
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::canvas::*;
+/// use rusty_nature_of_code::color::Rgba;
+///
+/// let mut canvas = RecordingCanvas::new( 640.0, 480.0 );
+/// canvas.clear( Rgba::BLACK );
+///
+/// assert_eq!( canvas.commands(), &[DrawCommand::Clear( Rgba::BLACK )] );
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct RecordingCanvas {
+	width: f32,
+	height: f32,
+	commands: Vec<DrawCommand>,
+}
+
+impl RecordingCanvas {
+	/// Creates an empty canvas of the given size
+	pub fn new( width: f32, height: f32 ) -> RecordingCanvas {
+		RecordingCanvas { width, height, commands: Vec::new() }
+	}
+
+	/// Every command drawn so far, in the order they were drawn
+	pub fn commands( &self ) -> &[DrawCommand] {
+		&self.commands
+	}
+
+	/// Returns the commands drawn so far, leaving the canvas empty
+	pub fn take( &mut self ) -> Vec<DrawCommand> {
+		std::mem::take( &mut self.commands )
+	}
+}
+
+impl Canvas for RecordingCanvas {
+	fn size( &self ) -> ( f32, f32 ) {
+		( self.width, self.height )
+	}
+
+	fn clear( &mut self, color: Rgba ) {
+		self.commands.push( DrawCommand::Clear( color ) );
+	}
+
+	fn circle( &mut self, center: Vec2, radius: f32, color: Rgba ) {
+		self.commands.push( DrawCommand::Circle { center, radius, color } );
+	}
+
+	fn line( &mut self, from: Vec2, to: Vec2, thickness: f32, color: Rgba ) {
+		self.commands.push( DrawCommand::Line { from, to, thickness, color } );
+	}
+
+	fn rect( &mut self, pos: Vec2, size: Vec2, color: Rgba ) {
+		self.commands.push( DrawCommand::Rect { pos, size, color } );
+	}
+
+	fn polygon( &mut self, points: &[Vec2], color: Rgba ) {
+		self.commands.push( DrawCommand::Polygon { points: points.to_vec(), color } );
+	}
+
+	fn text( &mut self, text: &str, pos: Vec2, size: f32, color: Rgba ) {
+		self.commands.push( DrawCommand::Text { text: text.to_string(), pos, size, color } );
+	}
+}
@@ -0,0 +1,93 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+
+/// A canvas which builds an svg document, so a frame of a sketch can be saved to a file
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::canvas::*;
+/// use rusty_nature_of_code::color::Rgba;
+///
+/// let mut canvas = SvgCanvas::new( 100.0, 100.0 );
+/// canvas.circle( Vec2::new( &50.0, &50.0 ), 10.0, Rgba::WHITE );
+///
+/// assert!( canvas.document().contains( "<circle" ) );
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct SvgCanvas {
+	width: f32,
+	height: f32,
+	body: String,
+}
+
+/// Formats a colour as svg fill or stroke attributes
+fn paint( attribute: &str, color: Rgba ) -> String {
+	format!( "{}=\"rgb({},{},{})\" {}-opacity=\"{:.3}\"", attribute, color.r, color.g, color.b, attribute, color.a as f32 / 255.0 )
+}
+
+/// Escapes the characters which have a meaning in xml
+fn escape( text: &str ) -> String {
+	text.replace( '&', "&amp;" ).replace( '<', "&lt;" ).replace( '>', "&gt;" )
+}
+
+impl SvgCanvas {
+	/// Creates an empty canvas of the given size
+	pub fn new( width: f32, height: f32 ) -> SvgCanvas {
+		SvgCanvas { width, height, body: String::new() }
+	}
+
+	/// The svg document for everything drawn so far
+	pub fn document( &self ) -> String {
+		format!( "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}</svg>\n",
+				 self.body, w = self.width, h = self.height )
+	}
+
+	/// Writes the svg document to path
+	pub fn save<P: AsRef<Path>>( &self, path: P ) -> io::Result<()> {
+		fs::write( path, self.document() )
+	}
+}
+
+impl Canvas for SvgCanvas {
+	fn size( &self ) -> ( f32, f32 ) {
+		( self.width, self.height )
+	}
+
+	fn clear( &mut self, color: Rgba ) {
+		/* Anything drawn before a clear can't be seen, so it is dropped from the document */
+		self.body.clear();
+		self.rect( Vec2::default(), Vec2::new( &self.width, &self.height ), color );
+	}
+
+	fn circle( &mut self, center: Vec2, radius: f32, color: Rgba ) {
+		let _ = writeln!( self.body, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>", center.x, center.y, radius, paint( "fill", color ) );
+	}
+
+	fn line( &mut self, from: Vec2, to: Vec2, thickness: f32, color: Rgba ) {
+		let _ = writeln!( self.body, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-width=\"{}\" {}/>",
+						  from.x, from.y, to.x, to.y, thickness, paint( "stroke", color ) );
+	}
+
+	fn rect( &mut self, pos: Vec2, size: Vec2, color: Rgba ) {
+		let _ = writeln!( self.body, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>", pos.x, pos.y, size.x, size.y, paint( "fill", color ) );
+	}
+
+	fn polygon( &mut self, points: &[Vec2], color: Rgba ) {
+		let points = points.iter().map( |p| format!( "{},{}", p.x, p.y ) ).collect::<Vec<_>>().join( " " );
+		let _ = writeln!( self.body, "<polygon points=\"{}\" {}/>", points, paint( "fill", color ) );
+	}
+
+	fn text( &mut self, text: &str, pos: Vec2, size: f32, color: Rgba ) {
+		/* Svg positions text by its baseline rather than its top, so it is moved down by roughly the height of a capital */
+		let _ = writeln!( self.body, "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" {}>{}</text>",
+						  pos.x, pos.y + size * 0.8, size, paint( "fill", color ), escape( text ) );
+	}
+}
@@ -0,0 +1,101 @@
+//! Writes frames drawn on a RasterCanvas to numbered png files or an animated gif, so images of the sketches can be
+//! produced without a display.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use crate::canvas::RasterCanvas;
+
+/// Writes the canvas to path as a png
+pub fn save_png<P: AsRef<Path>>( canvas: &RasterCanvas, path: P ) -> io::Result<()> {
+	let writer = BufWriter::new( File::create( path )? );
+	let mut encoder = png::Encoder::new( writer, canvas.width(), canvas.height() );
+	encoder.set_color( png::ColorType::Rgba );
+	encoder.set_depth( png::BitDepth::Eight );
+	let mut writer = encoder.write_header()?;
+	writer.write_image_data( canvas.pixels() )?;
+	Ok(())
+}
+
+/// Writes each frame to the next numbered png in a directory, e.g. frame_00000.png, frame_00001.png, ...
+///
+/// # Examples
+///
+/// ```no_run
+/// use rusty_nature_of_code::canvas::RasterCanvas;
+/// use rusty_nature_of_code::capture::PngSequence;
+///
+/// let canvas = RasterCanvas::new( 640, 480 );
+/// let mut frames = PngSequence::new( "frames", "frame" ).unwrap();
+/// frames.write( &canvas ).unwrap();
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct PngSequence {
+	dir: PathBuf,
+	prefix: String,
+	frame: usize,
+}
+
+impl PngSequence {
+	/// Creates the directory if it doesn't exist. Frames are named prefix_NNNNN.png
+	pub fn new<P: AsRef<Path>>( dir: P, prefix: &str ) -> io::Result<PngSequence> {
+		fs::create_dir_all( &dir )?;
+		Ok( PngSequence { dir: dir.as_ref().to_path_buf(), prefix: prefix.to_string(), frame: 0 } )
+	}
+
+	/// Writes the canvas as the next frame, returning the path written
+	pub fn write( &mut self, canvas: &RasterCanvas ) -> io::Result<PathBuf> {
+		let path = self.dir.join( format!( "{}_{:05}.png", self.prefix, self.frame ) );
+		save_png( canvas, &path )?;
+		self.frame += 1;
+		Ok( path )
+	}
+}
+
+/// Records frames into an animated gif which loops forever
+pub struct GifRecorder {
+	encoder: gif::Encoder<BufWriter<File>>,
+	width: u16,
+	height: u16,
+	delay: u16,
+}
+
+/// Converts a gif error into an io error, so capture only has one error type
+fn gif_error( e: gif::EncodingError ) -> io::Error {
+	match e {
+		gif::EncodingError::Io( e ) => e,
+		e => io::Error::new( io::ErrorKind::InvalidData, e ),
+	}
+}
+
+impl GifRecorder {
+	/// Creates the gif at path. Every frame must be width by height, and is shown for 1/fps seconds
+	pub fn create<P: AsRef<Path>>( path: P, width: u16, height: u16, fps: u32 ) -> io::Result<GifRecorder> {
+		let writer = BufWriter::new( File::create( path )? );
+		let mut encoder = gif::Encoder::new( writer, width, height, &[] ).map_err( gif_error )?;
+		encoder.set_repeat( gif::Repeat::Infinite ).map_err( gif_error )?;
+
+		/* Gif delays are in hundredths of a second */
+		let delay = ( 100 / fps.max( 1 ) ).max( 1 ) as u16;
+		Ok( GifRecorder { encoder, width, height, delay } )
+	}
+
+	/// Adds the canvas as the next frame
+	pub fn add_frame( &mut self, canvas: &RasterCanvas ) -> io::Result<()> {
+		if canvas.width() != self.width as u32 || canvas.height() != self.height as u32 {
+			return Err( io::Error::new( io::ErrorKind::InvalidInput,
+										format!( "frame is {}x{} but the gif is {}x{}", canvas.width(), canvas.height(), self.width, self.height ) ) );
+		}
+		let mut pixels = canvas.pixels().to_vec();
+		let mut frame = gif::Frame::from_rgba_speed( self.width, self.height, &mut pixels, 10 );
+		frame.delay = self.delay;
+		self.encoder.write_frame( &frame ).map_err( gif_error )
+	}
+
+	/// Finishes the gif, flushing it to its file
+	pub fn finish( self ) -> io::Result<()> {
+		self.encoder.into_inner()?.into_inner().map_err( |e| e.into_error() )?;
+		Ok(())
+	}
+}
@@ -0,0 +1,171 @@
+//! Static colliders, shapes which don't move that balls bounce off: circles, line segments, axis aligned boxes and convex
+//! polygons. Ramps, funnels, pegs and containers are built from them.
+//!
+//! A collider finds the contact with a ball, the direction to push the ball out along and how far it overlaps, and the
+//! ball bounces off along that normal. The normal of an angled surface is angled too, so balls reflect off ramps rather
+//! than just having their velocity flipped.
+
+use miscmath::prelude::*;
+
+/// A shape which doesn't move. Positions are in world coordinates, with (0,0) at the bottom left
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::collider::*;
+///
+/// /* A ramp sloping down to the right at 45 degrees, with a ball resting just into it */
+/// let ramp = Collider::Segment { from: Vec2::new( &0.0, &100.0 ), to: Vec2::new( &100.0, &0.0 ) };
+/// let contact = ramp.contact( &Vec2::new( &52.0, &52.0 ), 5.0 ).unwrap();
+///
+/// let half = std::f32::consts::FRAC_1_SQRT_2;
+/// assert!( ( contact.normal.x - half ).abs() < 0.0001 && ( contact.normal.y - half ).abs() < 0.0001 );
+/// assert!( ( contact.depth - ( 5.0 - 2.0 * std::f32::consts::SQRT_2 ) ).abs() < 0.0001 );
+///
+/// /* Balls clear of a box don't touch it */
+/// let block = Collider::Aabb { min: Vec2::new( &0.0, &0.0 ), max: Vec2::new( &20.0, &20.0 ) };
+/// assert_eq!( block.contact( &Vec2::new( &30.0, &10.0 ), 5.0 ), None );
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Collider {
+	/// A circle of radius around pos
+	Circle { pos: Vec2, radius: f32 },
+	/// A line segment, which balls bounce off either side and the ends of
+	Segment { from: Vec2, to: Vec2 },
+	/// An axis aligned box, from its bottom left corner min to its top right corner max
+	Aabb { min: Vec2, max: Vec2 },
+	/// A convex polygon, with its points in anticlockwise order
+	Polygon( Vec<Vec2> ),
+}
+
+/// Where a ball touches a collider
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact {
+	/// Unit vector pointing out of the collider towards the ball
+	pub normal: Vec2,
+	/// How far the ball overlaps the collider along the normal
+	pub depth: f32,
+}
+
+impl Collider {
+	/// A convex polygon from points in either order around it
+	pub fn polygon( mut points: Vec<Vec2> ) -> Collider {
+		if area( &points ) < 0.0 {
+			points.reverse();
+		}
+		Collider::Polygon( points )
+	}
+
+	/// The bottom left and top right corners of a box around the collider
+	pub fn bounds( &self ) -> ( Vec2, Vec2 ) {
+		match self {
+			Collider::Circle { pos, radius } => ( Vec2::new( &( pos.x - radius ), &( pos.y - radius ) ), Vec2::new( &( pos.x + radius ), &( pos.y + radius ) ) ),
+			Collider::Segment { from, to } => ( Vec2::new( &from.x.min( to.x ), &from.y.min( to.y ) ), Vec2::new( &from.x.max( to.x ), &from.y.max( to.y ) ) ),
+			Collider::Aabb { min, max } => ( *min, *max ),
+			Collider::Polygon( points ) => points.iter().fold( ( Vec2::new( &f32::MAX, &f32::MAX ), Vec2::new( &f32::MIN, &f32::MIN ) ), |( min, max ), p| {
+				( Vec2::new( &min.x.min( p.x ), &min.y.min( p.y ) ), Vec2::new( &max.x.max( p.x ), &max.y.max( p.y ) ) )
+			}),
+		}
+	}
+
+	/// The corners of a box or polygon in anticlockwise order, and the ends of a segment. Circles have none
+	pub fn points( &self ) -> Vec<Vec2> {
+		match self {
+			Collider::Circle { .. } => Vec::new(),
+			Collider::Segment { from, to } => vec![ *from, *to ],
+			Collider::Aabb { min, max } => vec![ *min, Vec2::new( &max.x, &min.y ), *max, Vec2::new( &min.x, &max.y ) ],
+			Collider::Polygon( points ) => points.clone(),
+		}
+	}
+
+	/// Whether every value is finite, circles have a radius greater than 0, boxes have min below and left of max, and
+	/// polygons have at least 3 points and are convex and anticlockwise
+	pub fn is_valid( &self ) -> bool {
+		let finite = self.points().iter().all( |p| p.x.is_finite() && p.y.is_finite() );
+		finite && match self {
+			Collider::Circle { pos, radius } => pos.x.is_finite() && pos.y.is_finite() && radius.is_finite() && *radius > 0.0,
+			Collider::Segment { .. } => true,
+			Collider::Aabb { min, max } => min.x < max.x && min.y < max.y,
+			Collider::Polygon( points ) => points.len() >= 3 && edges( points ).all( |( a, b )| {
+				/* Every corner turns left, so each point is on the inside of every other edge */
+				points.iter().all( |p| ( b.x - a.x ) * ( p.y - a.y ) - ( b.y - a.y ) * ( p.x - a.x ) >= 0.0 )
+			}),
+		}
+	}
+
+	/// Whether a ball of radius at pos reaches the box around the collider, so could be touching it
+	pub fn near( &self, pos: &Vec2, radius: f32 ) -> bool {
+		let ( min, max ) = self.bounds();
+		pos.x + radius >= min.x && pos.x - radius <= max.x && pos.y + radius >= min.y && pos.y - radius <= max.y
+	}
+
+	/// The contact between the collider and a ball of radius at pos, or None if they aren't touching
+	pub fn contact( &self, pos: &Vec2, radius: f32 ) -> Option<Contact> {
+		/* Skips colliders the ball can't reach, which is most of them, before working out the contact */
+		if !self.near( pos, radius ) {
+			return None;
+		}
+
+		match self {
+			Collider::Circle { pos: center, radius: reach } => away( pos, center, radius + reach ),
+			Collider::Segment { from, to } => match away( pos, &closest( pos, from, to ), radius ) {
+				/* A ball centred on the line is pushed out the left side of it, as there's no direction to push it */
+				None if closest( pos, from, to ) == *pos => unit( &Vec2::new( &( from.y - to.y ), &( to.x - from.x ) ) ).map( |normal| Contact { normal, depth: radius } ),
+				contact => contact,
+			},
+			Collider::Aabb { .. } | Collider::Polygon( .. ) => {
+				let points = self.points();
+
+				/* How far the ball's centre is outside each edge, along its outward normal. If it's inside every edge,
+				   the ball is pushed out through the closest one */
+				let ( normal, outside ) = edges( &points ).filter_map( |( a, b )| {
+					let normal = unit( &Vec2::new( &( b.y - a.y ), &( a.x - b.x ) ) )?;
+					Some( ( normal, ( pos.x - a.x ) * normal.x + ( pos.y - a.y ) * normal.y ) )
+				}).max_by( |a, b| a.1.total_cmp( &b.1 ) )?;
+				if outside <= 0.0 {
+					return Some( Contact { normal, depth: radius - outside } );
+				}
+
+				/* Otherwise the ball touches the closest point on the edges, which may be a corner */
+				let nearest = edges( &points ).map( |( a, b )| closest( pos, a, b ) )
+					.min_by( |a, b| a.dist_sq( pos ).total_cmp( &b.dist_sq( pos ) ) )?;
+				away( pos, &nearest, radius )
+			}
+		}
+	}
+}
+
+/// Each edge of a closed shape, from each point to the next
+pub(crate) fn edges( points: &[Vec2] ) -> impl Iterator<Item = ( &Vec2, &Vec2 )> {
+	points.iter().zip( points.iter().cycle().skip( 1 ) )
+}
+
+/// Twice the area of a polygon, positive when its points are anticlockwise
+pub(crate) fn area( points: &[Vec2] ) -> f32 {
+	edges( points ).map( |( a, b )| a.x * b.y - b.x * a.y ).sum()
+}
+
+/// v scaled to a length of 1, or None if it has no length
+pub(crate) fn unit( v: &Vec2 ) -> Option<Vec2> {
+	let len = ( v.x * v.x + v.y * v.y ).sqrt();
+	( len > 0.0 ).then( || Vec2::new( &( v.x / len ), &( v.y / len ) ) )
+}
+
+/// The closest point to pos on the segment from a to b
+fn closest( pos: &Vec2, a: &Vec2, b: &Vec2 ) -> Vec2 {
+	let ( dx, dy ) = ( b.x - a.x, b.y - a.y );
+	let len_sq = dx * dx + dy * dy;
+	let t = if len_sq > 0.0 { ( ( ( pos.x - a.x ) * dx + ( pos.y - a.y ) * dy ) / len_sq ).clamp( 0.0, 1.0 ) } else { 0.0 };
+	Vec2::new( &( a.x + dx * t ), &( a.y + dy * t ) )
+}
+
+/// The contact pushing a ball at pos away from point, if it's closer than reach
+fn away( pos: &Vec2, point: &Vec2, reach: f32 ) -> Option<Contact> {
+	let dist = pos.dist_sq( point ).sqrt();
+	if dist >= reach {
+		return None;
+	}
+	unit( &Vec2::new( &( pos.x - point.x ), &( pos.y - point.y ) ) ).map( |normal| Contact { normal, depth: reach - dist } )
+}
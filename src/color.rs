@@ -0,0 +1,91 @@
+//! A colour type which doesn't depend on a renderer, so the simulation data can be built without raylib.
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// A colour with red, green, blue and alpha channels from 0 to 255
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::color::Rgba;
+///
+/// let grey = Rgba::new( 128, 128, 128, 255 );
+///
+/// assert_eq!( grey.r, 128 );
+/// assert_eq!( grey.to_string(), "#808080" );
+/// assert_eq!( "#80808040".parse(), Ok( Rgba::new( 128, 128, 128, 64 ) ) );
+/// ```
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rgba {
+	/// Red channel
+	pub r: u8,
+	/// Green channel
+	pub g: u8,
+	/// Blue channel
+	pub b: u8,
+	/// Alpha channel, 0 is fully transparent and 255 is opaque
+	pub a: u8,
+}
+
+impl Rgba {
+	/// Opaque white
+	pub const WHITE: Rgba = Rgba::new( 255, 255, 255, 255 );
+	/// Opaque black
+	pub const BLACK: Rgba = Rgba::new( 0, 0, 0, 255 );
+	/// Opaque red
+	pub const RED: Rgba = Rgba::new( 230, 41, 55, 255 );
+
+	/// Creates a new colour from its channels
+	pub const fn new( r: u8, g: u8, b: u8, a: u8 ) -> Rgba {
+		Rgba { r, g, b, a }
+	}
+}
+
+/// Writes the colour as a hex string, "#rrggbb" if it's opaque and "#rrggbbaa" otherwise
+impl fmt::Display for Rgba {
+	fn fmt( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+		write!( f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b )?;
+		if self.a != 255 {
+			write!( f, "{:02x}", self.a )?;
+		}
+		Ok(())
+	}
+}
+
+/// Reads a colour from a hex string, "#rrggbb" or "#rrggbbaa"
+impl FromStr for Rgba {
+	type Err = String;
+
+	fn from_str( s: &str ) -> Result<Self, Self::Err> {
+		let error = || format!( "{:?} isn't a colour like \"#rrggbb\" or \"#rrggbbaa\"", s );
+		let hex = s.strip_prefix( '#' ).filter( |hex| ( hex.len() == 6 || hex.len() == 8 ) && hex.is_ascii() ).ok_or_else( error )?;
+		let channel = |i: usize| hex.get( i..i + 2 ).and_then( |channel| u8::from_str_radix( channel, 16 ).ok() ).ok_or_else( error );
+		let a = if hex.len() == 8 { channel( 6 )? } else { 255 };
+		Ok( Rgba::new( channel( 0 )?, channel( 2 )?, channel( 4 )?, a ) )
+	}
+}
+
+impl TryFrom<String> for Rgba {
+	type Error = String;
+
+	fn try_from( s: String ) -> Result<Self, Self::Error> {
+		s.parse()
+	}
+}
+
+impl From<Rgba> for String {
+	fn from( color: Rgba ) -> Self {
+		color.to_string()
+	}
+}
+
+#[cfg(feature = "render")]
+impl From<Rgba> for raylib::prelude::Color {
+	fn from( color: Rgba ) -> Self {
+		raylib::prelude::Color::new( color.r, color.g, color.b, color.a )
+	}
+}
@@ -0,0 +1,300 @@
+//! Parameters for the sketches, filled from a TOML file and command line flags, so experiments can be run without
+//! recompiling.
+//!
+//! Values are read in order: the defaults, then the file given with "--config FILE", then the other flags, which override
+//! the file. Anything left out keeps its default.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::bouncy_balls::scene::Scene;
+use crate::rng::SimRng;
+
+/// Every parameter the sketches read
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::config::Config;
+///
+/// let config = Config::from_toml( "
+/// seed = 7
+///
+/// [bouncy_balls]
+/// balls = 20
+/// gravity = -0.5
+/// " ).unwrap();
+///
+/// assert_eq!( config.seed, Some( 7 ) );
+/// assert_eq!( config.bouncy_balls.balls, 20 );
+/// assert_eq!( config.bouncy_balls.max_speed, 25.0 );
+///
+/// let args = [ "--balls", "0" ].map( String::from );
+/// assert!( Config::parse_args( args ).is_err() );
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+	/// Seed for the random number generator, a random seed is used if it isn't given
+	pub seed: Option<u64>,
+	pub window: WindowConfig,
+	pub bouncy_balls: BouncyBallsConfig,
+	pub smart_rockets: SmartRocketsConfig,
+	/// File to record a snapshot of every pass to, see record. Only set from the command line
+	#[serde(skip)]
+	pub record: Option<PathBuf>,
+	/// Recording to replay instead of running the sketch live. Only set from the command line
+	#[serde(skip)]
+	pub replay: Option<PathBuf>,
+	/// File to write the time each system takes to, as a Chrome trace if it ends in .json and CSV otherwise. Only set from
+	/// the command line
+	#[serde(skip)]
+	pub profile: Option<PathBuf>,
+}
+
+/// Size of the window or canvas. Left out, each sketch uses its own size
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+	pub width: Option<i32>,
+	pub height: Option<i32>,
+}
+
+/// Parameters of the bouncy balls and random walker sketches
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BouncyBallsConfig {
+	/// Number of balls kept on screen, new ones are spawned as others are dropped
+	pub balls: usize,
+	/// Acceleration applied to every ball each pass, negative is down
+	pub gravity: f32,
+	/// Largest speed a ball can have along each axis
+	pub max_speed: f32,
+	/// Number of columns in the grid of regions used to find collisions
+	pub columns: usize,
+	/// Number of rows in the grid of regions used to find collisions
+	pub rows: usize,
+	/// Scene file to load instead of spawning random balls. The scene's gravity and max speed replace the ones above
+	pub scene: Option<PathBuf>,
+}
+
+impl Default for BouncyBallsConfig {
+	fn default() -> Self {
+		BouncyBallsConfig { balls: 100, gravity: -0.9, max_speed: 25.0, columns: 10, rows: 10, scene: None }
+	}
+}
+
+/// Parameters of the smart rockets sketch
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmartRocketsConfig {
+	/// Number of rockets in each generation
+	pub rockets: usize,
+	/// Chance of each gene mutating when a new generation is bred
+	pub mutation_chance: f64,
+	/// Number of passes a generation lives for
+	pub lifespan: usize,
+}
+
+impl Default for SmartRocketsConfig {
+	fn default() -> Self {
+		SmartRocketsConfig { rockets: 10, mutation_chance: 0.1, lifespan: 10 }
+	}
+}
+
+/// Why a configuration couldn't be read
+#[derive(Debug)]
+pub enum ConfigError {
+	/// The config file couldn't be read
+	Io( PathBuf, io::Error ),
+	/// The config file isn't valid TOML, or has fields of the wrong type
+	Parse( String ),
+	/// A flag was given without a value, or with a value that isn't the right type
+	Flag( String, String ),
+	/// A value is out of range
+	Invalid( &'static str, String ),
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+		match self {
+			ConfigError::Io( path, e ) => write!( f, "couldn't read {}: {}", path.display(), e ),
+			ConfigError::Parse( message ) => write!( f, "invalid config: {}", message ),
+			ConfigError::Flag( flag, message ) => write!( f, "{} {}", flag, message ),
+			ConfigError::Invalid( field, message ) => write!( f, "{} {}", field, message ),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Parses the value following flag
+fn value<T: std::str::FromStr>( flag: &str, value: Option<String> ) -> Result<T, ConfigError> {
+	let value = value.ok_or_else( || ConfigError::Flag( flag.to_string(), "needs a value".to_string() ) )?;
+	value.parse().map_err( |_| ConfigError::Flag( flag.to_string(), format!( "can't be {:?}", value ) ) )
+}
+
+impl Config {
+
+	/// Flags parse_args understands, with a description of each, for usage messages
+	pub const FLAGS: &'static [(&'static str, &'static str)] = &[
+		( "--config FILE", "read parameters from a TOML file" ),
+		( "--seed N", "seed for the random number generator" ),
+		( "--width N", "window width in pixels" ),
+		( "--height N", "window height in pixels" ),
+		( "--balls N", "number of bouncy balls" ),
+		( "--gravity X", "vertical acceleration of the bouncy balls, negative is down" ),
+		( "--max-speed X", "largest speed of a bouncy ball along each axis" ),
+		( "--columns N", "columns in the collision grid" ),
+		( "--rows N", "rows in the collision grid" ),
+		( "--scene FILE", "bouncy balls scene to load" ),
+		( "--rockets N", "number of smart rockets" ),
+		( "--mutation X", "chance of each smart rocket gene mutating" ),
+		( "--lifespan N", "passes each generation of smart rockets lives for" ),
+		( "--record FILE", "record a snapshot of every pass to FILE" ),
+		( "--replay FILE", "replay a recording, arrows seek, backspace restarts, R carries on live" ),
+		( "--profile FILE", "write system timings to FILE, a Chrome trace if it ends in .json, CSV otherwise" ),
+	];
+
+	/// Parses a config from TOML. The result hasn't been validated
+	pub fn from_toml( toml: &str ) -> Result<Config, ConfigError> {
+		toml::from_str( toml ).map_err( |e| ConfigError::Parse( e.to_string() ) )
+	}
+
+	/// Reads a config from a TOML file. The result hasn't been validated
+	pub fn load<P: AsRef<Path>>( path: P ) -> Result<Config, ConfigError> {
+		let toml = fs::read_to_string( &path ).map_err( |e| ConfigError::Io( path.as_ref().to_path_buf(), e ) )?;
+		Config::from_toml( &toml )
+	}
+
+	/// The config as TOML, which can be saved and loaded back with from_toml
+	pub fn to_toml( &self ) -> String {
+		toml::to_string( self ).expect( "every config can be written as toml" )
+	}
+
+	/// Builds a validated config from command line arguments, not including the program name
+	///
+	/// Returns the config and any arguments which aren't config flags, in order, for the caller to handle.
+	pub fn parse_args<I: IntoIterator<Item = String>>( args: I ) -> Result<( Config, Vec<String> ), ConfigError> {
+		let args: Vec<String> = args.into_iter().collect();
+
+		/* The file is read first wherever it is, so the other flags override it */
+		let mut config = match args.iter().position( |arg| arg == "--config" ) {
+			Some( i ) => Config::load( value::<PathBuf>( "--config", args.get( i + 1 ).cloned() )? )?,
+			None => Config::default(),
+		};
+
+		let mut rest = Vec::new();
+		let mut args = args.into_iter();
+		while let Some( flag ) = args.next() {
+			match flag.as_str() {
+				"--config" => { args.next(); },
+				"--seed" => config.seed = Some( value( &flag, args.next() )? ),
+				"--width" => config.window.width = Some( value( &flag, args.next() )? ),
+				"--height" => config.window.height = Some( value( &flag, args.next() )? ),
+				"--balls" => config.bouncy_balls.balls = value( &flag, args.next() )?,
+				"--gravity" => config.bouncy_balls.gravity = value( &flag, args.next() )?,
+				"--max-speed" => config.bouncy_balls.max_speed = value( &flag, args.next() )?,
+				"--columns" => config.bouncy_balls.columns = value( &flag, args.next() )?,
+				"--rows" => config.bouncy_balls.rows = value( &flag, args.next() )?,
+				"--scene" => config.bouncy_balls.scene = Some( value( &flag, args.next() )? ),
+				"--rockets" => config.smart_rockets.rockets = value( &flag, args.next() )?,
+				"--mutation" => config.smart_rockets.mutation_chance = value( &flag, args.next() )?,
+				"--lifespan" => config.smart_rockets.lifespan = value( &flag, args.next() )?,
+				"--record" => config.record = Some( value( &flag, args.next() )? ),
+				"--replay" => config.replay = Some( value( &flag, args.next() )? ),
+				"--profile" => config.profile = Some( value( &flag, args.next() )? ),
+				_ => rest.push( flag ),
+			}
+		}
+
+		config.validate()?;
+		Ok( ( config, rest ) )
+	}
+
+	/// Builds a validated config from the program's arguments, exiting with a message if they are invalid
+	///
+	/// Any arguments which aren't config flags are also an error, so this suits binaries with no flags of their own.
+	pub fn from_args() -> Config {
+		let exit = |message: String| -> ! {
+			eprintln!( "{}", message );
+			eprintln!( "flags:" );
+			for ( flag, description ) in Config::FLAGS {
+				eprintln!( "  {:<16} {}", flag, description );
+			}
+			std::process::exit( 2 );
+		};
+		match Config::parse_args( std::env::args().skip( 1 ) ) {
+			Ok( ( config, rest ) ) if rest.is_empty() => config,
+			Ok( ( _, rest ) ) => exit( format!( "unknown argument {}", rest[0] ) ),
+			Err( e ) => exit( e.to_string() ),
+		}
+	}
+
+	/// Checks every value is in range
+	pub fn validate( &self ) -> Result<(), ConfigError> {
+		let invalid = |field, message: &str| Err( ConfigError::Invalid( field, message.to_string() ) );
+
+		if self.window.width.is_some_and( |width| width <= 0 ) {
+			return invalid( "window.width", "must be greater than 0" );
+		}
+		if self.window.height.is_some_and( |height| height <= 0 ) {
+			return invalid( "window.height", "must be greater than 0" );
+		}
+
+		let balls = &self.bouncy_balls;
+		if balls.balls == 0 {
+			return invalid( "bouncy_balls.balls", "must be at least 1" );
+		}
+		if !balls.gravity.is_finite() {
+			return invalid( "bouncy_balls.gravity", "must be a finite number" );
+		}
+		if !( balls.max_speed.is_finite() && balls.max_speed > 0.0 ) {
+			return invalid( "bouncy_balls.max_speed", "must be greater than 0" );
+		}
+		/* Entities are clamped to between the second and last region, so there must be at least two */
+		if !( 2..=100 ).contains( &balls.columns ) {
+			return invalid( "bouncy_balls.columns", "must be from 2 to 100" );
+		}
+		if !( 2..=100 ).contains( &balls.rows ) {
+			return invalid( "bouncy_balls.rows", "must be from 2 to 100" );
+		}
+		if let Some( path ) = &balls.scene {
+			if let Err( e ) = Scene::load( path ) {
+				return Err( ConfigError::Invalid( "bouncy_balls.scene", format!( "{} couldn't be loaded: {}", path.display(), e ) ) );
+			}
+		}
+
+		let rockets = &self.smart_rockets;
+		if rockets.rockets == 0 {
+			return invalid( "smart_rockets.rockets", "must be at least 1" );
+		}
+		if !( 0.0..=1.0 ).contains( &rockets.mutation_chance ) {
+			return invalid( "smart_rockets.mutation_chance", "must be from 0 to 1" );
+		}
+		if rockets.lifespan == 0 {
+			return invalid( "smart_rockets.lifespan", "must be at least 1" );
+		}
+
+		if self.record.is_some() && self.replay.is_some() {
+			return invalid( "record", "can't be used while replaying" );
+		}
+		Ok(())
+	}
+
+	/// Creates the random number generator from the seed, or a random seed if there isn't one
+	pub fn rng( &self ) -> SimRng {
+		match self.seed {
+			Some( seed ) => SimRng::new( seed ),
+			None => SimRng::from_entropy(),
+		}
+	}
+
+	/// The window size, using the sketch's own size for any dimension which isn't set
+	pub fn size( &self, default: ( i32, i32 ) ) -> ( i32, i32 ) {
+		( self.window.width.unwrap_or( default.0 ), self.window.height.unwrap_or( default.1 ) )
+	}
+}
@@ -0,0 +1,165 @@
+//! Systems which draw what the other systems see rather than what the sketch looks like: the collision grid, velocities
+//! and accelerations, collision pairs and entity ids. Sketches draw them from Sketch::debug, which the runner toggles.
+
+use std::collections::HashMap;
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use crate::bouncy_balls::data::Attributes;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::events::Collision;
+use crate::store::Components;
+
+/// Colour of the grid lines and region counts
+const GRID_COLOR: Rgba = Rgba::new( 0, 120, 255, 160 );
+/// Colour of a region holding the most entities, emptier regions are more transparent
+const OCCUPIED_COLOR: Rgba = Rgba::new( 0, 120, 255, 90 );
+/// Colour of velocity arrows
+const VEL_COLOR: Rgba = Rgba::new( 0, 200, 0, 255 );
+/// Colour of acceleration arrows
+const ACC_COLOR: Rgba = Rgba::new( 230, 0, 230, 255 );
+/// Colour of collision pairs
+const COLLISION_COLOR: Rgba = Rgba::new( 255, 0, 0, 255 );
+/// Colour of entity id labels
+const ID_COLOR: Rgba = Rgba::new( 90, 90, 90, 255 );
+
+/// Velocities are drawn this many times longer, so a pass's movement is visible
+pub const VEL_SCALE: f32 = 5.0;
+/// Accelerations are drawn this many times longer
+pub const ACC_SCALE: f32 = 20.0;
+
+/// Draws an arrow from `from` to `to`, in screen coordinates
+fn arrow<C: Canvas + ?Sized>( canvas: &mut C, from: Vec2, to: Vec2, color: Rgba ) {
+	let ( dx, dy ) = ( to.x - from.x, to.y - from.y );
+	let len = ( dx * dx + dy * dy ).sqrt();
+	if len < 0.5 {
+		return;
+	}
+	canvas.line( from, to, 1.5, color );
+
+	/* The head is two lines angled back from the tip */
+	let head = len.min( 6.0 );
+	let ( ux, uy ) = ( dx / len, dy / len );
+	for side in [ -1.0, 1.0 ] {
+		let back = Vec2::new( &( to.x - head * ( ux + side * 0.5 * uy ) ), &( to.y - head * ( uy - side * 0.5 * ux ) ) );
+		canvas.line( to, back, 1.5, color );
+	}
+}
+
+/// Outlines the regions used to find collisions, shading each by how many entities are in it and labelling the count
+///
+/// Regions are laid out over the canvas the way movement_system places entities in them, with row 0 at the bottom. The
+/// movement system never uses the first row or column, so entities on those edges are counted in the second.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::bouncy_balls::data::regions;
+/// use rusty_nature_of_code::canvas::*;
+/// use rusty_nature_of_code::debug::grid_debug_system;
+///
+/// let mut canvas = RecordingCanvas::new( 100.0, 100.0 );
+/// grid_debug_system( &mut canvas, &regions( 4, 2 ) );
+///
+/// /* Empty regions aren't shaded, so only the 5 vertical and 3 horizontal lines are drawn */
+/// assert_eq!( canvas.commands().len(), 8 );
+/// ```
+///
+pub fn grid_debug_system<C: Canvas + ?Sized>( canvas: &mut C, regions: &[Vec<HashMap<u64, Entity>>] ) {
+	let ( width, height ) = canvas.size();
+	let rows = regions.len();
+	let columns = regions.first().map_or( 0, Vec::len );
+	if rows == 0 || columns == 0 {
+		return;
+	}
+	let ( cell_w, cell_h ) = ( width / columns as f32, height / rows as f32 );
+
+	let most = regions.iter().flatten().map( HashMap::len ).max().unwrap_or( 0 ).max( 1 );
+	for ( row, cells ) in regions.iter().enumerate() {
+		for ( column, region ) in cells.iter().enumerate() {
+			if region.is_empty() {
+				continue;
+			}
+			/* Rows count up from the bottom of the screen, so the top of a cell is found by flipping its upper edge */
+			let top_left = canvas.flip( &Vec2::new( &( column as f32 * cell_w ), &( ( row + 1 ) as f32 * cell_h ) ) );
+			let alpha = ( OCCUPIED_COLOR.a as f32 * region.len() as f32 / most as f32 ).round() as u8;
+			canvas.rect( top_left, Vec2::new( &cell_w, &cell_h ), Rgba { a: alpha.max( 20 ), ..OCCUPIED_COLOR } );
+			let label = Vec2::new( &( top_left.x + 3.0 ), &( top_left.y + 3.0 ) );
+			canvas.text( &region.len().to_string(), label, 10.0, GRID_COLOR );
+		}
+	}
+
+	for column in 0..=columns {
+		let x = column as f32 * cell_w;
+		canvas.line( Vec2::new( &x, &0.0 ), Vec2::new( &x, &height ), 1.0, GRID_COLOR );
+	}
+	for row in 0..=rows {
+		let y = row as f32 * cell_h;
+		canvas.line( Vec2::new( &0.0, &y ), Vec2::new( &width, &y ), 1.0, GRID_COLOR );
+	}
+}
+
+/// Draws each entity's velocity and acceleration as arrows from its position, scaled by VEL_SCALE and ACC_SCALE
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn vector_debug_system< C: Canvas + ?Sized, P: Components<Vec2>, V: Components<Vec2>, A: Components<Vec2> >(canvas: &mut C,
+																								pos_store: &P,
+																								vel_store: &V,
+																								acc_store: &A ) {
+	pos_store.for_each( | entity, pos | {
+		let from = canvas.flip( pos );
+		for ( vec, scale, color ) in [ ( vel_store.get( entity ), VEL_SCALE, VEL_COLOR ), ( acc_store.get( entity ), ACC_SCALE, ACC_COLOR ) ] {
+			if let Some( vec ) = vec {
+				let to = canvas.flip( &Vec2::new( &( pos.x + vec.x * scale ), &( pos.y + vec.y * scale ) ) );
+				arrow( canvas, from, to, color );
+			}
+		}
+	});
+}
+
+/// Highlights pairs of entities found colliding, e.g. the collisions returned by collision_system, with a line between
+/// their centres and a ring around each
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn collision_debug_system< C: Canvas + ?Sized, P: Components<Vec2>, A: Components<Attributes> >(canvas: &mut C,
+																						 collisions: &[Collision],
+																						 pos_store: &P,
+																						 atr_store: &A ) {
+	for Collision { a: entity_a, b: entity_b, .. } in collisions {
+		/* The pair may have been dropped since it was found */
+		let ( Some( pos_a ), Some( pos_b ) ) = ( pos_store.get( *entity_a ), pos_store.get( *entity_b ) ) else {
+			continue;
+		};
+		let ( a, b ) = ( canvas.flip( pos_a ), canvas.flip( pos_b ) );
+		canvas.line( a, b, 2.0, COLLISION_COLOR );
+		for ( entity, pos ) in [ ( entity_a, a ), ( entity_b, b ) ] {
+			let radius = atr_store.get( *entity ).map_or( 4.0, | atr | atr.radius );
+			canvas.circle( pos, radius + 3.0, Rgba { a: 70, ..COLLISION_COLOR } );
+		}
+	}
+}
+
+/// Labels each entity with the last 4 hex digits of its id, enough to tell entities apart on screen
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn id_debug_system< C: Canvas + ?Sized, P: Components<Vec2> >( canvas: &mut C, pos_store: &P ) {
+	pos_store.for_each( | entity, pos | {
+		let pos = canvas.flip( pos );
+		canvas.text( &format!( "{:04x}", entity.id() & 0xffff ), Vec2::new( &( pos.x + 6.0 ), &( pos.y - 14.0 ) ), 10.0, ID_COLOR );
+	});
+}
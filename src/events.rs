@@ -0,0 +1,150 @@
+//! Typed events systems send to tell other systems what happened, e.g. which entities collided, so effects, statistics
+//! and scoring don't need to repeat the work of finding out.
+//!
+//! Each event type has an Events queue, added to a World with World::add_event. Events are kept until the start of the
+//! next pass, when the clear_events hook empties every queue, so systems later in the same pass, including those sent
+//! from the input stage, and the render and debug systems drawing it, can all read them.
+
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use crate::sketch::Context;
+use crate::world::World;
+
+/// The events of one type sent since the queue was last cleared, in the order they were sent
+///
+/// # Examples
+///
+/// ```
+/// use misc_ecs::prelude::*;
+/// use rusty_nature_of_code::events::*;
+/// use rusty_nature_of_code::world::World;
+///
+/// let mut world = World::new();
+/// world.add_event::<WallHit>();
+///
+/// let entity = EntityManager::new().next();
+/// world.send( WallHit { entity, side: Side::Bottom } );
+/// assert_eq!( world.events::<WallHit>().iter().map( |hit| hit.side ).collect::<Vec<_>>(), [ Side::Bottom ] );
+///
+/// world.clear_events();
+/// assert!( world.events::<WallHit>().is_empty() );
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Events<E> {
+	events: Vec<E>,
+}
+
+impl<E> Default for Events<E> {
+	fn default() -> Self {
+		Events::new()
+	}
+}
+
+impl<E> Events<E> {
+	pub fn new() -> Events<E> {
+		Events { events: Vec::new() }
+	}
+
+	pub fn send( &mut self, event: E ) {
+		self.events.push( event );
+	}
+
+	pub fn as_slice( &self ) -> &[E] {
+		&self.events
+	}
+
+	pub fn iter( &self ) -> std::slice::Iter<'_, E> {
+		self.events.iter()
+	}
+
+	pub fn len( &self ) -> usize {
+		self.events.len()
+	}
+
+	pub fn is_empty( &self ) -> bool {
+		self.events.is_empty()
+	}
+
+	pub fn clear( &mut self ) {
+		self.events.clear();
+	}
+}
+
+impl<E> Extend<E> for Events<E> {
+	fn extend<I: IntoIterator<Item = E>>( &mut self, events: I ) {
+		self.events.extend( events );
+	}
+}
+
+/// An edge of the screen
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+	Left,
+	Right,
+	Bottom,
+	Top,
+}
+
+impl Side {
+	/// Unit vector pointing out of the screen through the side
+	pub fn outward( &self ) -> Vec2 {
+		match self {
+			Side::Left => Vec2::new( &-1.0, &0.0 ),
+			Side::Right => Vec2::new( &1.0, &0.0 ),
+			Side::Bottom => Vec2::new( &0.0, &-1.0 ),
+			Side::Top => Vec2::new( &0.0, &1.0 ),
+		}
+	}
+}
+
+/// Two entities collided, and a was pushed by impulse, its change in momentum
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Collision {
+	pub a: Entity,
+	pub b: Entity,
+	pub impulse: Vec2,
+}
+
+/// An entity bounced off an edge of the screen
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WallHit {
+	pub entity: Entity,
+	pub side: Side,
+}
+
+/// An entity wrapped around from an edge of the screen to the opposite one
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Wrapped {
+	pub entity: Entity,
+	/// The edge it left through
+	pub side: Side,
+}
+
+/// An entity was spawned, with every component in its bundle. Sent by World::spawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spawned {
+	pub entity: Entity,
+}
+
+/// Why an entity was despawned
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DespawnReason {
+	/// It left the screen through an edge which despawns entities
+	OutOfBounds,
+	/// It came to rest on the bottom edge
+	AtRest,
+}
+
+/// An entity was despawned. It's no longer live, so its components are gone
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Despawned {
+	pub entity: Entity,
+	pub reason: DespawnReason,
+}
+
+/// Empties every event queue, so each pass starts without the last one's events. Added to a schedule with
+/// Schedule::at_start, so it runs before the input stage
+pub fn clear_events( world: &mut World, _ctx: &mut Context ) {
+	world.clear_events();
+}
@@ -4,4 +4,25 @@
 //! and his "The Nature of Code 2" video series on his YouTube channel "The Coding Train".
 
 pub mod bouncy_balls;
-pub mod smart_rockets;
\ No newline at end of file
+pub mod smart_rockets;
+pub mod rng;
+pub mod config;
+pub mod snapshot;
+pub mod record;
+pub mod color;
+pub mod canvas;
+pub mod debug;
+pub mod profile;
+pub mod schedule;
+pub mod query;
+pub mod store;
+pub mod world;
+pub mod events;
+pub mod material;
+pub mod boundary;
+pub mod collider;
+pub mod shape;
+pub mod sketch;
+pub mod sketches;
+#[cfg(feature = "capture")]
+pub mod capture;
\ No newline at end of file
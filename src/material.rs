@@ -0,0 +1,214 @@
+//! Materials describe how a surface responds to being hit: how much it bounces, and how much friction it has.
+//!
+//! When two surfaces touch, their materials are combined. Restitutions are multiplied, so a wall with a restitution of
+//! 1.0 keeps whatever bounce the ball hitting it has. Frictions take the geometric mean, so a frictionless surface is
+//! frictionless whatever touches it.
+//!
+//! Contacts are resolved with an impulse, a change in momentum, along the normal between the surfaces and friction along
+//! the surface. A wall doesn't move, so it's treated as having infinite mass and the mass of what hits it cancels out.
+//! Impulses on bodies which aren't point masses act where they touch, so one off to the side of a body spins it too.
+
+use miscmath::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::component;
+
+/// How a surface bounces and slides
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::material::*;
+///
+/// /* A rubber ball and a steel ball dropped on the same floor at the same speed */
+/// let floor = Material { restitution: 1.0, static_friction: 0.4, dynamic_friction: 0.3 };
+/// let up = Vec2::new( &0.0, &1.0 );
+/// let falling = Vec2::new( &0.0, &-5.0 );
+///
+/// let rubber = contact_impulse( &falling, &up, 1.0, &Material::RUBBER.combine( &floor ) ).unwrap();
+/// let steel = contact_impulse( &falling, &up, 1.0, &Material::STEEL.combine( &floor ) ).unwrap();
+/// assert!( rubber.y > steel.y );
+///
+/// /* Something already moving away from a surface doesn't bounce off it */
+/// assert_eq!( contact_impulse( &Vec2::new( &0.0, &5.0 ), &up, 1.0, &floor ), None );
+/// ```
+///
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Material {
+	/// Fraction of the speed towards the surface kept when bouncing off it, 0.0 doesn't bounce and 1.0 loses nothing
+	pub restitution: f32,
+	/// Stops a sliding surface when the impulse needed to is less than this times the impulse into the surface
+	pub static_friction: f32,
+	/// Slows a sliding surface by this times the impulse into the surface
+	pub dynamic_friction: f32,
+}
+
+component!(
+	/// Material of an entity's surface. Entities without one use Material::default
+	Mat => Material,
+);
+
+impl Default for Material {
+	/// A fairly bouncy surface with a little friction, the material balls have when a scene doesn't give one
+	fn default() -> Self {
+		Material { restitution: 0.8, static_friction: 0.3, dynamic_friction: 0.2 }
+	}
+}
+
+impl Material {
+	/// Bounces almost as high as it's dropped from, and grips what it touches
+	pub const RUBBER: Material = Material { restitution: 0.9, static_friction: 1.0, dynamic_friction: 0.8 };
+	/// Bounces a little, and slides easily
+	pub const STEEL: Material = Material { restitution: 0.5, static_friction: 0.15, dynamic_friction: 0.1 };
+	/// Bounces a little, and grips more than steel
+	pub const WOOD: Material = Material { restitution: 0.5, static_friction: 0.5, dynamic_friction: 0.4 };
+	/// Barely bounces, and barely grips
+	pub const ICE: Material = Material { restitution: 0.2, static_friction: 0.05, dynamic_friction: 0.02 };
+	/// Keeps the bounce of what hits it, with a little friction, the material of walls and obstacles when a scene doesn't give one
+	pub const WALL: Material = Material { restitution: 1.0, static_friction: 0.1, dynamic_friction: 0.05 };
+	/// Loses nothing bouncing, and has no friction
+	pub const ELASTIC: Material = Material { restitution: 1.0, static_friction: 0.0, dynamic_friction: 0.0 };
+
+	/// The material of a contact between this surface and other
+	pub fn combine( &self, other: &Material ) -> Material {
+		Material {
+			restitution: self.restitution * other.restitution,
+			static_friction: ( self.static_friction * other.static_friction ).sqrt(),
+			dynamic_friction: ( self.dynamic_friction * other.dynamic_friction ).sqrt(),
+		}
+	}
+
+	/// Whether every value is finite and not negative
+	pub fn is_valid( &self ) -> bool {
+		[ self.restitution, self.static_friction, self.dynamic_friction ].iter().all( |value| value.is_finite() && *value >= 0.0 )
+	}
+}
+
+/// The impulse to apply to a body touching a surface, or None if it's already moving away from it
+///
+/// rel_vel is the body's velocity relative to the surface, normal the unit vector pointing out of the surface towards the
+/// body, and inv_mass the sum of the inverse masses of the body and the surface, which is 0.0 for a wall. The body gains
+/// the impulse and the surface, if it moves, loses it.
+pub fn contact_impulse( rel_vel: &Vec2, normal: &Vec2, inv_mass: f32, material: &Material ) -> Option<Vec2> {
+	let along_normal = rel_vel.x * normal.x + rel_vel.y * normal.y;
+	if along_normal >= 0.0 || inv_mass <= 0.0 {
+		return None;
+	}
+
+	/* Reverses the velocity along the normal, keeping the material's restitution of it */
+	let normal_impulse = -( 1.0 + material.restitution ) * along_normal / inv_mass;
+	let mut impulse = Vec2::new( &( normal.x * normal_impulse ), &( normal.y * normal_impulse ) );
+
+	/* Friction acts against the velocity along the surface. Static friction stops it outright if it can, otherwise dynamic
+	   friction slows it, but never past stopping */
+	let ( tangent_x, tangent_y ) = ( rel_vel.x - along_normal * normal.x, rel_vel.y - along_normal * normal.y );
+	let sliding = ( tangent_x * tangent_x + tangent_y * tangent_y ).sqrt();
+	if sliding > 0.0 {
+		let stop = sliding / inv_mass;
+		let friction = match stop <= material.static_friction * normal_impulse {
+			true => stop,
+			false => ( material.dynamic_friction * normal_impulse ).min( stop ),
+		};
+		impulse.x -= tangent_x / sliding * friction;
+		impulse.y -= tangent_y / sliding * friction;
+	}
+	Some( impulse )
+}
+
+/// How a body moves and how hard it is to move, for working out the impulses at its contacts. Walls and obstacles don't
+/// move, so have no inverse mass or inertia
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Motion {
+	/// Position of the body's centre of mass
+	pub pos: Vec2,
+	pub vel: Vec2,
+	/// Angular velocity, in radians anticlockwise per pass
+	pub spin: f32,
+	/// 1.0 over the body's mass
+	pub inv_mass: f32,
+	/// 1.0 over the body's moment of inertia, its resistance to being spun
+	pub inv_inertia: f32,
+}
+
+impl Motion {
+	/// Something at pos which doesn't move, whatever hits it
+	pub fn fixed( pos: Vec2 ) -> Motion {
+		Motion { pos, ..Motion::default() }
+	}
+
+	/// Velocity of the point of the body at point, which is faster the further it is from the centre of a spinning body
+	pub fn velocity_at( &self, point: &Vec2 ) -> Vec2 {
+		Vec2::new( &( self.vel.x - self.spin * ( point.y - self.pos.y ) ), &( self.vel.y + self.spin * ( point.x - self.pos.x ) ) )
+	}
+
+	/// Applies impulse at point, changing the body's velocity and, if the point is off centre, its spin
+	pub fn apply( &mut self, impulse: &Vec2, point: &Vec2 ) {
+		self.vel.x += impulse.x * self.inv_mass;
+		self.vel.y += impulse.y * self.inv_mass;
+		self.spin += cross( &Vec2::new( &( point.x - self.pos.x ), &( point.y - self.pos.y ) ), impulse ) * self.inv_inertia;
+	}
+}
+
+/// Bounces b off a where they touch at point, with the normal the unit vector from a towards b, returning the impulse b
+/// gained, or None if they're already moving apart there
+///
+/// Like contact_impulse, but the impulse and friction act at the contact point rather than the centres of the bodies, so
+/// a contact off to one side of a body spins it as well as pushing it.
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::material::*;
+///
+/// /* A falling body hitting the floor to the right of its centre is pushed up, and spun anticlockwise */
+/// let mut floor = Motion::fixed( Vec2::new( &0.0, &0.0 ) );
+/// let mut body = Motion { pos: Vec2::new( &0.0, &10.0 ), vel: Vec2::new( &0.0, &-5.0 ), spin: 0.0, inv_mass: 1.0, inv_inertia: 0.01 };
+/// rigid_impulse( &mut floor, &mut body, &Vec2::new( &8.0, &0.0 ), &Vec2::new( &0.0, &1.0 ), &Material::ELASTIC ).unwrap();
+///
+/// assert!( body.vel.y > -5.0 && body.spin > 0.0 );
+/// assert_eq!( floor, Motion::fixed( Vec2::new( &0.0, &0.0 ) ) );
+/// ```
+///
+pub fn rigid_impulse( a: &mut Motion, b: &mut Motion, point: &Vec2, normal: &Vec2, material: &Material ) -> Option<Vec2> {
+	let ( rel_a, rel_b ) = ( Vec2::new( &( point.x - a.pos.x ), &( point.y - a.pos.y ) ), Vec2::new( &( point.x - b.pos.x ), &( point.y - b.pos.y ) ) );
+	let ( vel_a, vel_b ) = ( a.velocity_at( point ), b.velocity_at( point ) );
+	let rel_vel = Vec2::new( &( vel_b.x - vel_a.x ), &( vel_b.y - vel_a.y ) );
+	let along_normal = rel_vel.x * normal.x + rel_vel.y * normal.y;
+
+	/* How much an impulse of 1.0 along a direction changes the bodies' relative velocity along it, which includes how much
+	   of it goes into spinning them */
+	let inv_mass = |direction: &Vec2| a.inv_mass + b.inv_mass
+		+ cross( &rel_a, direction ).powi( 2 ) * a.inv_inertia + cross( &rel_b, direction ).powi( 2 ) * b.inv_inertia;
+	if along_normal >= 0.0 || inv_mass( normal ) <= 0.0 {
+		return None;
+	}
+
+	/* Reverses the velocity along the normal, keeping the material's restitution of it */
+	let normal_impulse = -( 1.0 + material.restitution ) * along_normal / inv_mass( normal );
+	let mut impulse = Vec2::new( &( normal.x * normal_impulse ), &( normal.y * normal_impulse ) );
+
+	/* Friction acts against the sliding of the contact point, as in contact_impulse */
+	let ( tangent_x, tangent_y ) = ( rel_vel.x - along_normal * normal.x, rel_vel.y - along_normal * normal.y );
+	let sliding = ( tangent_x * tangent_x + tangent_y * tangent_y ).sqrt();
+	if sliding > 0.0 {
+		let tangent = Vec2::new( &( tangent_x / sliding ), &( tangent_y / sliding ) );
+		let stop = sliding / inv_mass( &tangent );
+		let friction = match stop <= material.static_friction * normal_impulse {
+			true => stop,
+			false => ( material.dynamic_friction * normal_impulse ).min( stop ),
+		};
+		impulse.x -= tangent.x * friction;
+		impulse.y -= tangent.y * friction;
+	}
+
+	b.apply( &impulse, point );
+	a.apply( &Vec2::new( &-impulse.x, &-impulse.y ), point );
+	Some( impulse )
+}
+
+/// The z component of the cross product of a and b, how much b turns anticlockwise about the origin when applied at a
+fn cross( a: &Vec2, b: &Vec2 ) -> f32 {
+	a.x * b.y - a.y * b.x
+}
@@ -0,0 +1,243 @@
+//! Timing of each system a sketch runs, shown as a bar chart of rolling averages and optionally written to a CSV or
+//! Chrome trace file.
+//!
+//! Systems run by a Schedule are timed under their names, anything else can be timed by wrapping it in Profiler::time,
+//! using the profiler in the Context. The runner ends each pass with Profiler::end_pass, which adds the pass's timings to
+//! the averages and returns them for a TraceWriter.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+
+/// Number of passes each average is taken over
+pub const WINDOW: usize = 60;
+
+/// One run of a system
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+	/// Name the system was timed under
+	pub name: &'static str,
+	/// When the system started, since the profiler was created
+	pub start: Duration,
+	pub duration: Duration,
+}
+
+/// Timings of one system over the last WINDOW passes it ran in
+#[derive(Clone, Debug)]
+struct Timings {
+	name: &'static str,
+	passes: VecDeque<Duration>,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+	/// Time the profiler was created, event start times are measured from it
+	epoch: Instant,
+	/// Every system timed so far, in the order they were first run
+	systems: Vec<Timings>,
+	/// Events of the current pass
+	events: Vec<Event>,
+}
+
+/// Times systems and keeps a rolling average for each
+///
+/// Timing only needs a shared reference, so systems can be timed from Sketch::draw as well as Sketch::update.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::profile::Profiler;
+///
+/// let profiler = Profiler::new();
+/// let sum = profiler.time( "sum", || ( 0..1000 ).sum::<u64>() );
+/// profiler.time( "render", || () );
+///
+/// let events = profiler.end_pass();
+/// assert_eq!( sum, 499500 );
+/// assert_eq!( events.iter().map( |event| event.name ).collect::<Vec<_>>(), [ "sum", "render" ] );
+/// assert_eq!( profiler.averages().len(), 2 );
+/// assert!( profiler.end_pass().is_empty() );
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct Profiler {
+	state: RefCell<State>,
+}
+
+impl Default for Profiler {
+	fn default() -> Self {
+		Profiler::new()
+	}
+}
+
+impl Profiler {
+	pub fn new() -> Profiler {
+		Profiler { state: RefCell::new( State { epoch: Instant::now(), systems: Vec::new(), events: Vec::new() } ) }
+	}
+
+	/// Runs system, timing it under name, and returns what it returns
+	pub fn time<R, F: FnOnce() -> R>( &self, name: &'static str, system: F ) -> R {
+		let start = Instant::now();
+		let result = system();
+		self.record( name, start );
+		result
+	}
+
+	/// Records a run of the system called name, from start until now. For systems which can't be run in a closure passed
+	/// to time, e.g. because they need the context the profiler is in
+	pub fn record( &self, name: &'static str, start: Instant ) {
+		let duration = start.elapsed();
+		let mut state = self.state.borrow_mut();
+		let start = start.saturating_duration_since( state.epoch );
+		state.events.push( Event { name, start, duration } );
+	}
+
+	/// Adds the current pass's timings to the averages, and returns its events in the order they ran
+	///
+	/// A system run more than once in a pass counts as one run of their total time.
+	pub fn end_pass( &self ) -> Vec<Event> {
+		let mut state = self.state.borrow_mut();
+		let events = std::mem::take( &mut state.events );
+
+		let mut totals: Vec<( &'static str, Duration )> = Vec::new();
+		for event in events.iter() {
+			match totals.iter_mut().find( |( name, _ )| *name == event.name ) {
+				Some( ( _, total ) ) => *total += event.duration,
+				None => totals.push( ( event.name, event.duration ) ),
+			}
+		}
+		for ( name, total ) in totals {
+			let position = state.systems.iter().position( |timings| timings.name == name );
+			let timings = match position {
+				Some( i ) => &mut state.systems[i],
+				None => {
+					state.systems.push( Timings { name, passes: VecDeque::with_capacity( WINDOW ) } );
+					state.systems.last_mut().unwrap()
+				}
+			};
+			if timings.passes.len() == WINDOW {
+				timings.passes.pop_front();
+			}
+			timings.passes.push_back( total );
+		}
+		events
+	}
+
+	/// The average time each system took over the last WINDOW passes it ran in, in the order they were first run
+	pub fn averages( &self ) -> Vec<( &'static str, Duration )> {
+		self.state.borrow().systems.iter().map( |timings| {
+			let total: Duration = timings.passes.iter().sum();
+			( timings.name, total / timings.passes.len().max( 1 ) as u32 )
+		}).collect()
+	}
+}
+
+/// Draws the averages as a bar chart in the bottom left of the canvas, scaled so the slowest system fills the bar width
+pub fn draw_profile( canvas: &mut dyn Canvas, profiler: &Profiler ) {
+	const ROW: f32 = 16.0;
+	const BAR: f32 = 160.0;
+	let averages = profiler.averages();
+	if averages.is_empty() {
+		return;
+	}
+
+	let height = canvas.size().1;
+	let top = height - ROW * averages.len() as f32 - 12.0;
+	canvas.rect( Vec2::new( &0.0, &( top - 6.0 ) ), Vec2::new( &( BAR + 200.0 ), &( height - top + 6.0 ) ), Rgba::new( 0, 0, 0, 180 ) );
+
+	let slowest = averages.iter().map( |( _, average )| average.as_secs_f32() ).fold( 0.0, f32::max ).max( f32::EPSILON );
+	for ( i, ( name, average ) ) in averages.iter().enumerate() {
+		let y = top + ROW * i as f32;
+		let width = BAR * average.as_secs_f32() / slowest;
+		canvas.rect( Vec2::new( &110.0, &( y + 2.0 ) ), Vec2::new( &width.max( 1.0 ), &( ROW - 4.0 ) ), Rgba::new( 255, 160, 0, 255 ) );
+		canvas.text( name, Vec2::new( &8.0, &y ), 10.0, Rgba::WHITE );
+		let label = format!( "{:.3} ms", average.as_secs_f64() * 1000.0 );
+		canvas.text( &label, Vec2::new( &( 120.0 + BAR ), &y ), 10.0, Rgba::WHITE );
+	}
+}
+
+/// Format of a timing dump
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+	/// One line per event: pass, system, start and duration in microseconds
+	Csv,
+	/// Chrome's trace event format, which can be opened in chrome://tracing or Perfetto
+	Chrome,
+}
+
+/// Writes events to a CSV or Chrome trace file as they come in
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use rusty_nature_of_code::profile::*;
+///
+/// let event = Event { name: "collision", start: Duration::from_micros( 10 ), duration: Duration::from_micros( 5 ) };
+/// let mut trace = TraceWriter::new( Vec::new(), TraceFormat::Csv ).unwrap();
+/// trace.write( 3, &[ event ] ).unwrap();
+///
+/// let csv = String::from_utf8( trace.finish().unwrap() ).unwrap();
+/// assert_eq!( csv, "pass,system,start_us,duration_us\n3,collision,10,5\n" );
+/// ```
+///
+pub struct TraceWriter<W: Write = BufWriter<File>> {
+	out: W,
+	format: TraceFormat,
+	/// Whether an event has been written, so Chrome events know to be separated by commas
+	written: bool,
+}
+
+impl TraceWriter {
+	/// Creates the file at path, in Chrome's format if it ends in .json and as CSV otherwise
+	pub fn create<P: AsRef<Path>>( path: P ) -> io::Result<TraceWriter> {
+		let format = match path.as_ref().extension().is_some_and( |extension| extension == "json" ) {
+			true => TraceFormat::Chrome,
+			false => TraceFormat::Csv,
+		};
+		TraceWriter::new( BufWriter::new( File::create( path )? ), format )
+	}
+}
+
+impl<W: Write> TraceWriter<W> {
+	/// Writes the start of the file to out
+	pub fn new( mut out: W, format: TraceFormat ) -> io::Result<TraceWriter<W>> {
+		match format {
+			TraceFormat::Csv => writeln!( out, "pass,system,start_us,duration_us" )?,
+			TraceFormat::Chrome => write!( out, "[" )?,
+		}
+		Ok( TraceWriter { out, format, written: false } )
+	}
+
+	/// Writes the events of a pass
+	pub fn write( &mut self, pass: usize, events: &[Event] ) -> io::Result<()> {
+		for event in events {
+			let ( start, duration ) = ( event.start.as_micros(), event.duration.as_micros() );
+			match self.format {
+				TraceFormat::Csv => writeln!( self.out, "{},{},{},{}", pass, event.name, start, duration )?,
+				TraceFormat::Chrome => {
+					let separator = if self.written { "," } else { "" };
+					write!( self.out, "{}\n{{\"name\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1,\"args\":{{\"pass\":{}}}}}",
+							separator, event.name, start, duration, pass )?;
+				}
+			}
+			self.written = true;
+		}
+		Ok(())
+	}
+
+	/// Ends the file, flushes it and returns the output. Chrome can still read a trace which wasn't finished
+	pub fn finish( mut self ) -> io::Result<W> {
+		if self.format == TraceFormat::Chrome {
+			writeln!( self.out, "\n]" )?;
+		}
+		self.out.flush()?;
+		Ok( self.out )
+	}
+}
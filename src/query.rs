@@ -0,0 +1,281 @@
+//! Iterating the entities which have several components at once, over misc_ecs's stores and DenseStores.
+//!
+//! Terms take the Components trait the systems are bounded by rather than misc_ecs's Store, as DenseStore can't implement
+//! Store (see store). HashStore implements Components, so it's passed to a query as it is, and any other Store is passed
+//! wrapped in a StoreAdapter.
+//!
+//! A query is a tuple of terms, each taking one store: read and write give the entity's component, optional gives an
+//! Option which is None when the entity doesn't have it, and with and without only match entities which do or don't
+//! have the component. Entities missing a required component are skipped rather than panicking.
+//!
+//! Queries iterate the entities of the smallest store with a required term, so at least one of read, write or with is
+//! needed; a query of only optional and without terms matches nothing.
+
+use std::marker::PhantomData;
+use misc_ecs::prelude::*;
+use crate::store::Components;
+
+/// Fetching a term's item for an entity, borrowed from its store for 'a
+pub trait Fetch<'a> {
+	type Item;
+
+	/// The item for entity, or None if the term doesn't match it
+	fn fetch( &'a mut self, entity: Entity ) -> Option<Self::Item>;
+}
+
+/// Part of a query, which fetches an item for each entity it matches. Component types must be 'static, so items can be
+/// borrowed for any lifetime
+pub trait Term: for<'a> Fetch<'a> {
+	/// Number of entities the term could match, if it requires a component
+	fn candidates( &self ) -> Option<usize>;
+
+	/// The entities the term could match, if it requires a component
+	fn entities( &self ) -> Option<Vec<Entity>>;
+}
+
+/// The item a term gives for each entity
+pub type Item<'a, Q> = <Q as Fetch<'a>>::Item;
+
+/// Gives a reference to each entity's component
+pub struct Read<'s, T, S: Components<T>>( &'s S, PhantomData<T> );
+/// Gives a mutable reference to each entity's component
+pub struct Write<'s, T, S: Components<T>>( &'s mut S, PhantomData<T> );
+/// Gives Some of the inner term's item for entities it matches, and None for the rest
+pub struct Optional<Q: Term>( Q );
+/// Only matches entities with the component, without fetching it
+pub struct With<'s, T, S: Components<T>>( &'s S, PhantomData<T> );
+/// Only matches entities without the component
+pub struct Without<'s, T, S: Components<T>>( &'s S, PhantomData<T> );
+
+/// A term reading the components in store
+pub fn read<T, S: Components<T>>( store: &S ) -> Read<'_, T, S> {
+	Read( store, PhantomData )
+}
+
+/// A term changing the components in store
+pub fn write<T, S: Components<T>>( store: &mut S ) -> Write<'_, T, S> {
+	Write( store, PhantomData )
+}
+
+/// Makes a term optional
+pub fn optional<Q: Term>( term: Q ) -> Optional<Q> {
+	Optional( term )
+}
+
+/// A filter matching entities with a component in store
+pub fn with<T, S: Components<T>>( store: &S ) -> With<'_, T, S> {
+	With( store, PhantomData )
+}
+
+/// A filter matching entities without a component in store
+pub fn without<T, S: Components<T>>( store: &S ) -> Without<'_, T, S> {
+	Without( store, PhantomData )
+}
+
+fn keys<T, S: Components<T>>( store: &S ) -> Vec<Entity> {
+	let mut entities = Vec::with_capacity( store.len() );
+	store.for_each( |entity, _| entities.push( entity ) );
+	entities
+}
+
+impl<'a, T: 'static, S: Components<T>> Fetch<'a> for Read<'_, T, S> {
+	type Item = &'a T;
+
+	fn fetch( &'a mut self, entity: Entity ) -> Option<&'a T> {
+		self.0.get( entity )
+	}
+}
+
+impl<T: 'static, S: Components<T>> Term for Read<'_, T, S> {
+	fn candidates( &self ) -> Option<usize> {
+		Some( self.0.len() )
+	}
+
+	fn entities( &self ) -> Option<Vec<Entity>> {
+		Some( keys( self.0 ) )
+	}
+}
+
+impl<'a, T: 'static, S: Components<T>> Fetch<'a> for Write<'_, T, S> {
+	type Item = &'a mut T;
+
+	fn fetch( &'a mut self, entity: Entity ) -> Option<&'a mut T> {
+		self.0.get_mut( entity )
+	}
+}
+
+impl<T: 'static, S: Components<T>> Term for Write<'_, T, S> {
+	fn candidates( &self ) -> Option<usize> {
+		Some( self.0.len() )
+	}
+
+	fn entities( &self ) -> Option<Vec<Entity>> {
+		Some( keys( self.0 ) )
+	}
+}
+
+impl<'a, Q: Term> Fetch<'a> for Optional<Q> {
+	type Item = Option<Item<'a, Q>>;
+
+	fn fetch( &'a mut self, entity: Entity ) -> Option<Self::Item> {
+		Some( self.0.fetch( entity ) )
+	}
+}
+
+impl<Q: Term> Term for Optional<Q> {
+	fn candidates( &self ) -> Option<usize> {
+		None
+	}
+
+	fn entities( &self ) -> Option<Vec<Entity>> {
+		None
+	}
+}
+
+impl<T: 'static, S: Components<T>> Fetch<'_> for With<'_, T, S> {
+	type Item = ();
+
+	fn fetch( &mut self, entity: Entity ) -> Option<()> {
+		self.0.get( entity ).map( |_| () )
+	}
+}
+
+impl<T: 'static, S: Components<T>> Term for With<'_, T, S> {
+	fn candidates( &self ) -> Option<usize> {
+		Some( self.0.len() )
+	}
+
+	fn entities( &self ) -> Option<Vec<Entity>> {
+		Some( keys( self.0 ) )
+	}
+}
+
+impl<T: 'static, S: Components<T>> Fetch<'_> for Without<'_, T, S> {
+	type Item = ();
+
+	fn fetch( &mut self, entity: Entity ) -> Option<()> {
+		match self.0.get( entity ) {
+			Some( _ ) => None,
+			None => Some( () ),
+		}
+	}
+}
+
+impl<T: 'static, S: Components<T>> Term for Without<'_, T, S> {
+	fn candidates( &self ) -> Option<usize> {
+		None
+	}
+
+	fn entities( &self ) -> Option<Vec<Entity>> {
+		None
+	}
+}
+
+/// Implements Term for a tuple of terms, matching the entities every term matches
+macro_rules! tuple_term {
+	( $( $term:ident ),+ ) => {
+		#[allow(non_snake_case)]
+		impl<'a, $( $term: Term ),+ > Fetch<'a> for ( $( $term, )+ ) {
+			type Item = ( $( Item<'a, $term>, )+ );
+
+			fn fetch( &'a mut self, entity: Entity ) -> Option<Self::Item> {
+				let ( $( $term, )+ ) = self;
+				Some( ( $( $term.fetch( entity )?, )+ ) )
+			}
+		}
+
+		#[allow(non_snake_case)]
+		impl< $( $term: Term ),+ > Term for ( $( $term, )+ ) {
+			fn candidates( &self ) -> Option<usize> {
+				let ( $( $term, )+ ) = self;
+				[ $( $term.candidates() ),+ ].into_iter().flatten().min()
+			}
+
+			fn entities( &self ) -> Option<Vec<Entity>> {
+				let ( $( $term, )+ ) = self;
+				let smallest = self.candidates()?;
+				$(
+					if $term.candidates() == Some( smallest ) {
+						return $term.entities();
+					}
+				)+
+				None
+			}
+		}
+	};
+}
+
+tuple_term!( A );
+tuple_term!( A, B );
+tuple_term!( A, B, C );
+tuple_term!( A, B, C, D );
+tuple_term!( A, B, C, D, E );
+tuple_term!( A, B, C, D, E, F );
+
+/// The entities matching every term of a tuple, and their items
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use misc_ecs::prelude::*;
+/// use rusty_nature_of_code::query::*;
+///
+/// let mut entity_manager = EntityManager::new();
+/// let ( mut pos_store, mut vel_store, mut acc_store, mut frozen_store ) = ( HashStore::new(), HashStore::new(), HashStore::new(), HashStore::new() );
+/// let ( a, b, c ) = ( entity_manager.next(), entity_manager.next(), entity_manager.next() );
+/// for entity in [ a, b, c ] {
+///     pos_store.add( entity, Vec2::default() );
+/// }
+/// vel_store.add( a, Vec2::new( &1.0, &0.0 ) );
+/// vel_store.add( b, Vec2::new( &0.0, &1.0 ) );
+/// acc_store.add( a, Vec2::new( &1.0, &1.0 ) );
+/// frozen_store.add( b, () );
+///
+/// /* c has no velocity and b is frozen, so only a moves. a also has an acceleration, which is optional */
+/// query( ( write( &mut pos_store ), read( &vel_store ), optional( read( &acc_store ) ), without( &frozen_store ) ) )
+///     .for_each( |_, ( pos, vel, acc, _ )| {
+///         pos.add( vel );
+///         if let Some( acc ) = acc {
+///             pos.add( acc );
+///         }
+///     });
+///
+/// assert_eq!( *pos_store.get( a ).unwrap(), Vec2::new( &2.0, &1.0 ) );
+/// assert_eq!( *pos_store.get( b ).unwrap(), Vec2::default() );
+/// assert_eq!( query( ( read( &pos_store ), with( &frozen_store ) ) ).entities(), vec![ b ] );
+/// ```
+///
+pub struct Query<Q: Term>( Q );
+
+/// Creates a query from a tuple of terms
+pub fn query<Q: Term>( terms: Q ) -> Query<Q> {
+	Query( terms )
+}
+
+impl<Q: Term> Query<Q> {
+	/// The entities matching every term, in no particular order
+	pub fn entities( &mut self ) -> Vec<Entity> {
+		let candidates = self.0.entities().unwrap_or_default();
+		candidates.into_iter().filter( |entity| self.0.fetch( *entity ).is_some() ).collect()
+	}
+
+	/// Number of entities matching every term
+	pub fn count( &mut self ) -> usize {
+		self.entities().len()
+	}
+
+	/// The items for entity, if it matches every term
+	pub fn get( &mut self, entity: Entity ) -> Option<Item<'_, Q>> {
+		self.0.fetch( entity )
+	}
+
+	/// Runs f on each entity matching every term, with its items
+	pub fn for_each<F: for<'a> FnMut( Entity, Item<'a, Q> )>( &mut self, mut f: F ) {
+		for entity in self.0.entities().unwrap_or_default() {
+			if let Some( items ) = self.0.fetch( entity ) {
+				f( entity, items );
+			}
+		}
+	}
+}
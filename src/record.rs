@@ -0,0 +1,342 @@
+//! Recording a sketch to a compact binary log of per-pass snapshots, and replaying the log frame by frame.
+//!
+//! A log starts with MAGIC and a header naming the sketch and the config it was run with. Every frame after that is the
+//! sketch's saved state and the random number generator after a pass, each written as a little endian u32 length
+//! followed by the frame encoded with bincode, so a log cut short by a crash can still be read up to its last whole frame.
+//!
+//! Replaying loads each frame's state rather than updating the sketch, so it matches the recording exactly.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::config::Config;
+use crate::rng::SimRng;
+use crate::sketch::{Context, Input, Key, Sketch};
+use crate::snapshot;
+
+/// Bytes every log starts with, ending in the format version
+pub const MAGIC: &[u8; 8] = b"NOCREC\0\x01";
+
+/// What was recorded
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+	/// Title of the recorded sketch
+	pub title: String,
+	/// Seed the sketch was run with
+	pub seed: u64,
+	/// The config the sketch was run with, as TOML
+	pub config: String,
+}
+
+impl Header {
+	/// Describes a run of sketch with ctx
+	pub fn new( sketch: &dyn Sketch, ctx: &Context ) -> Header {
+		let config = Config { seed: Some( ctx.rng.seed() ), ..ctx.config.clone() };
+		Header { title: sketch.title().to_string(), seed: ctx.rng.seed(), config: config.to_toml() }
+	}
+
+	/// The config the sketch was run with
+	pub fn config( &self ) -> io::Result<Config> {
+		Config::from_toml( &self.config ).map_err( |e| io::Error::new( io::ErrorKind::InvalidData, e ) )
+	}
+}
+
+/// The state of a sketch after a pass
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Frame {
+	/// Number of passes the sketch had been updated for
+	pub pass: usize,
+	/// The random number generator, so a replay can be resumed live from this frame
+	pub rng: SimRng,
+	/// State written by Sketch::save
+	pub state: Vec<u8>,
+}
+
+/// Writes a length prefixed block
+fn write_block<W: Write>( out: &mut W, bytes: &[u8] ) -> io::Result<()> {
+	out.write_all( &( bytes.len() as u32 ).to_le_bytes() )?;
+	out.write_all( bytes )
+}
+
+/// Reads a length prefixed block, None at the end of the input or if the block is cut short
+fn read_block<R: Read>( input: &mut R ) -> io::Result<Option<Vec<u8>>> {
+	let mut len = [0; 4];
+	let mut bytes = Vec::new();
+	match input.read_exact( &mut len ) {
+		Err( e ) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok( None ),
+		result => result?,
+	}
+	let len = u32::from_le_bytes( len ) as usize;
+	input.take( len as u64 ).read_to_end( &mut bytes )?;
+	Ok( ( bytes.len() == len ).then_some( bytes ) )
+}
+
+/// Writes frames of a sketch to a log
+pub struct Recorder<W: Write = BufWriter<File>> {
+	out: W,
+	/// Pass of the last frame written, so passes which didn't update the sketch aren't written again
+	last: Option<usize>,
+}
+
+impl Recorder {
+	/// Creates the log file at path, for the run of sketch with ctx
+	pub fn create<P: AsRef<Path>>( path: P, sketch: &dyn Sketch, ctx: &Context ) -> io::Result<Recorder> {
+		Recorder::new( BufWriter::new( File::create( path )? ), sketch, ctx )
+	}
+
+	/// Creates a recorder for the file config.record names, if there is one
+	pub fn from_config( sketch: &dyn Sketch, ctx: &Context ) -> io::Result<Option<Recorder>> {
+		ctx.config.record.as_ref().map( |path| Recorder::create( path, sketch, ctx ) ).transpose()
+	}
+}
+
+impl<W: Write> Recorder<W> {
+	/// Writes the header to out. Fails if the sketch can't be saved
+	pub fn new( mut out: W, sketch: &dyn Sketch, ctx: &Context ) -> io::Result<Recorder<W>> {
+		if sketch.save().is_none() {
+			return Err( io::Error::new( io::ErrorKind::Unsupported, format!( "{} can't be recorded", sketch.title() ) ) );
+		}
+		out.write_all( MAGIC )?;
+		write_block( &mut out, &snapshot::encode( &Header::new( sketch, ctx ) ) )?;
+		Ok( Recorder { out, last: None } )
+	}
+
+	/// Writes the sketch's current state, unless it was already written for this pass
+	pub fn record( &mut self, sketch: &dyn Sketch, ctx: &Context ) -> io::Result<()> {
+		if self.last == Some( ctx.frame ) {
+			return Ok(());
+		}
+		let state = sketch.save().ok_or_else( || io::Error::new( io::ErrorKind::Unsupported, "sketch can't be saved" ) )?;
+		write_block( &mut self.out, &snapshot::encode( &Frame { pass: ctx.frame, rng: ctx.rng.clone(), state } ) )?;
+		self.last = Some( ctx.frame );
+		Ok(())
+	}
+
+	/// Flushes and returns the output
+	pub fn finish( mut self ) -> io::Result<W> {
+		self.out.flush()?;
+		Ok( self.out )
+	}
+}
+
+/// A log read back into memory
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::record::*;
+/// use rusty_nature_of_code::rng::SimRng;
+/// use rusty_nature_of_code::sketch::*;
+/// use rusty_nature_of_code::sketches::RandomWalkerOop;
+///
+/// let mut sketch = RandomWalkerOop::new();
+/// let mut ctx = Context::new( ( 640, 480 ), SimRng::new( 3 ) );
+/// sketch.setup( &mut ctx );
+///
+/// let mut recorder = Recorder::new( Vec::new(), &sketch, &ctx ).unwrap();
+/// let mut saved = Vec::new();
+/// for _ in 0..10 {
+///     sketch.update( &mut ctx );
+///     ctx.frame += 1;
+///     recorder.record( &sketch, &ctx ).unwrap();
+///     saved.push( sketch.save().unwrap() );
+/// }
+///
+/// let recording = Recording::read( &recorder.finish().unwrap()[..] ).unwrap();
+/// assert_eq!( recording.header.title, "random walker oop" );
+/// assert_eq!( recording.frames.len(), 10 );
+///
+/// /* Loading a frame gives back exactly the state it was recorded from */
+/// let mut replayed = RandomWalkerOop::new();
+/// replayed.load( &recording.frames[4].state ).unwrap();
+/// assert_eq!( replayed.save().unwrap(), saved[4] );
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct Recording {
+	pub header: Header,
+	pub frames: Vec<Frame>,
+}
+
+impl Recording {
+	/// Reads a log from a file
+	pub fn load<P: AsRef<Path>>( path: P ) -> io::Result<Recording> {
+		Recording::read( BufReader::new( File::open( path )? ) )
+	}
+
+	/// Reads a log, ignoring a last frame which was cut short
+	pub fn read<R: Read>( mut input: R ) -> io::Result<Recording> {
+		let invalid = |message: &str| io::Error::new( io::ErrorKind::InvalidData, message.to_string() );
+
+		let mut magic = [0; 8];
+		input.read_exact( &mut magic ).map_err( |_| invalid( "not a recording" ) )?;
+		if magic[..6] != MAGIC[..6] {
+			return Err( invalid( "not a recording" ) );
+		}
+		if magic != *MAGIC {
+			return Err( invalid( "recording is from a different version" ) );
+		}
+
+		let header = read_block( &mut input )?.ok_or_else( || invalid( "recording has no header" ) )?;
+		let header = snapshot::decode( &header )?;
+		let mut frames = Vec::new();
+		while let Some( frame ) = read_block( &mut input )? {
+			frames.push( snapshot::decode( &frame )? );
+		}
+		Ok( Recording { header, frames } )
+	}
+
+	/// Reads the log config.replay names, if there is one, and replaces config with the one it was recorded with. Where to
+	/// write timings is kept, as it doesn't change the sketch
+	pub fn from_config( config: &mut Config ) -> io::Result<Option<Recording>> {
+		let Some( path ) = config.replay.clone() else {
+			return Ok( None );
+		};
+		let recording = Recording::load( &path )?;
+		*config = Config { replay: Some( path ), profile: config.profile.take(), ..recording.header.config()? };
+		Ok( Some( recording ) )
+	}
+}
+
+/// Keys which seek through a replay, and how many frames each moves
+pub const SEEK_KEYS: [( Key, isize ); 4] = [ ( Key::Left, -1 ), ( Key::Right, 1 ), ( Key::Down, -60 ), ( Key::Up, 60 ) ];
+/// Key which goes back to the first frame of a replay
+pub const START_KEY: Key = Key::Backspace;
+/// Key which stops replaying and carries on updating the sketch live from the current frame
+pub const LIVE_KEY: Key = Key::Char( 'r' );
+
+/// Plays a recording back through the sketch it was recorded from
+///
+/// Each update loads the next frame instead of updating the sketch. The arrow keys seek, and the replay pauses on its last
+/// frame.
+pub struct Replay<'a> {
+	sketch: &'a mut dyn Sketch,
+	recording: Recording,
+	/// Index of the frame loaded into the sketch
+	current: usize,
+	/// Whether the sketch is being updated itself rather than from the recording
+	live: bool,
+}
+
+impl<'a> Replay<'a> {
+	/// Fails if the recording is of a different sketch or has no frames
+	pub fn new( sketch: &'a mut dyn Sketch, recording: Recording ) -> io::Result<Replay<'a>> {
+		if recording.header.title != sketch.title() {
+			let message = format!( "recording is of {}, not {}", recording.header.title, sketch.title() );
+			return Err( io::Error::new( io::ErrorKind::InvalidInput, message ) );
+		}
+		if recording.frames.is_empty() {
+			return Err( io::Error::new( io::ErrorKind::InvalidData, "recording has no frames" ) );
+		}
+		Ok( Replay { sketch, recording, current: 0, live: false } )
+	}
+
+	/// Index of the frame loaded into the sketch
+	pub fn current( &self ) -> usize {
+		self.current
+	}
+
+	/// Number of frames in the recording
+	pub fn len( &self ) -> usize {
+		self.recording.frames.len()
+	}
+
+	/// Whether the recording has no frames, which Replay::new doesn't allow
+	pub fn is_empty( &self ) -> bool {
+		self.recording.frames.is_empty()
+	}
+
+	/// Loads frame into the sketch, clamped to the recording, and stops it running live
+	pub fn seek( &mut self, frame: usize, ctx: &mut Context ) -> io::Result<()> {
+		self.current = frame.min( self.len() - 1 );
+		self.live = false;
+		let frame = &self.recording.frames[self.current];
+		self.sketch.load( &frame.state )?;
+		ctx.frame = frame.pass;
+		ctx.rng = frame.rng.clone();
+		Ok(())
+	}
+
+	/// Seeks, reporting rather than returning errors as the Sketch methods can't return them
+	fn seek_or_report( &mut self, frame: usize, ctx: &mut Context ) {
+		if let Err( e ) = self.seek( frame, ctx ) {
+			eprintln!( "failed to load frame {}: {}", frame, e );
+		}
+	}
+}
+
+impl Sketch for Replay<'_> {
+	fn title( &self ) -> &str {
+		self.sketch.title()
+	}
+
+	fn size( &self ) -> ( i32, i32 ) {
+		self.sketch.size()
+	}
+
+	fn background( &self ) -> Option<Rgba> {
+		self.sketch.background()
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		/* Setup builds whatever isn't saved, like a scene's obstacles, before the first frame replaces the rest */
+		self.sketch.setup( ctx );
+		self.seek_or_report( 0, ctx );
+	}
+
+	fn update( &mut self, ctx: &mut Context ) {
+		if self.live {
+			self.sketch.update( ctx );
+		} else if self.current + 1 < self.len() {
+			self.seek_or_report( self.current + 1, ctx );
+			/* step counts this pass once it's drawn, as it did when it was recorded */
+			ctx.frame = ctx.frame.saturating_sub( 1 );
+		} else {
+			ctx.paused = true;
+		}
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, ctx: &Context ) {
+		self.sketch.draw( canvas, ctx );
+	}
+
+	fn debug( &self, canvas: &mut dyn Canvas, ctx: &Context ) {
+		self.sketch.debug( canvas, ctx );
+	}
+
+	fn input( &mut self, input: &Input, ctx: &mut Context ) {
+		for ( key, frames ) in SEEK_KEYS {
+			if input.pressed( key ) {
+				self.seek_or_report( self.current.saturating_add_signed( frames ), ctx );
+			}
+		}
+		if input.pressed( START_KEY ) {
+			self.seek_or_report( 0, ctx );
+		}
+		if input.pressed( LIVE_KEY ) {
+			self.live = true;
+		}
+		self.sketch.input( input, ctx );
+	}
+
+	fn hud( &self, ctx: &Context ) -> Vec<String> {
+		let mut hud = self.sketch.hud( ctx );
+		if self.live {
+			hud.push( "Live".to_string() );
+		} else {
+			hud.push( format!( "Replay = {}/{}", self.current, self.len() - 1 ) );
+		}
+		hud
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		self.sketch.save()
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		self.sketch.load( state )
+	}
+}
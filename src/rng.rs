@@ -0,0 +1,97 @@
+//! Seedable random number generation shared by every system, so a simulation can be reproduced from its seed.
+
+use std::ops::Range;
+use miscmath::prelude::Vec2;
+use rand::distributions::uniform::{SampleRange, SampleUniform};
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// The random number generator resource passed into systems and spawn code in place of thread_rng
+///
+/// Two SimRngs created with the same seed produce the same sequence of numbers on every platform.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::rng::SimRng;
+///
+/// let mut a = SimRng::new( 42 );
+/// let mut b = SimRng::new( 42 );
+///
+/// assert_eq!( a.range( 0..1000 ), b.range( 0..1000 ) );
+/// ```
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimRng {
+	seed: u64,
+	rng: ChaCha8Rng,
+}
+
+impl SimRng {
+
+	/// Creates a SimRng from a seed
+	pub fn new( seed: u64 ) -> SimRng {
+		SimRng { seed, rng: ChaCha8Rng::seed_from_u64( seed ) }
+	}
+
+	/// Creates a SimRng with a random seed, which can still be read back with seed() to reproduce the run
+	pub fn from_entropy() -> SimRng {
+		SimRng::new( thread_rng().next_u64() )
+	}
+
+	/// The seed this SimRng was created with
+	pub fn seed( &self ) -> u64 {
+		self.seed
+	}
+
+	/// Creates an independent SimRng for stream, e.g. one per thread, which is the same for the same seed and stream
+	pub fn fork( &self, stream: u64 ) -> SimRng {
+		let mut rng = ChaCha8Rng::seed_from_u64( self.seed );
+		rng.set_stream( stream );
+		SimRng { seed: self.seed, rng }
+	}
+
+	/// Generates a random number in the range provided, a seeded replacement for miscmath::random
+	pub fn range<T: SampleUniform>( &mut self, rng: Range<T> ) -> T
+	where
+		Range<T>: SampleRange<T>,
+	{
+		self.rng.gen_range( rng )
+	}
+
+	/// Returns true with the probability p
+	pub fn chance( &mut self, p: f64 ) -> bool {
+		self.rng.gen_bool( p )
+	}
+
+	/// Generates a Vec2 with components in the ranges provided, a seeded replacement for Vec2::create_random2
+	pub fn vec2( &mut self, range_x: &Range<f32>, range_y: &Range<f32> ) -> Vec2 {
+		let x = self.range( range_x.clone() );
+		let y = self.range( range_y.clone() );
+		Vec2::new( &x, &y )
+	}
+
+	/// Generates a Vec2 with an angle in the range provided, a seeded replacement for Vec2::from_rand_angle
+	pub fn from_angle( &mut self, range: &Range<f32>, mag: &Option<f32> ) -> Vec2 {
+		Vec2::from_angle( &self.range( range.clone() ), mag )
+	}
+}
+
+impl RngCore for SimRng {
+	fn next_u32( &mut self ) -> u32 {
+		self.rng.next_u32()
+	}
+
+	fn next_u64( &mut self ) -> u64 {
+		self.rng.next_u64()
+	}
+
+	fn fill_bytes( &mut self, dest: &mut [u8] ) {
+		self.rng.fill_bytes( dest )
+	}
+
+	fn try_fill_bytes( &mut self, dest: &mut [u8] ) -> Result<(), rand::Error> {
+		self.rng.try_fill_bytes( dest )
+	}
+}
\ No newline at end of file
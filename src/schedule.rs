@@ -0,0 +1,358 @@
+//! Running a sketch's systems in a fixed order of named stages, so systems can be written once and composed into
+//! different sketches without each sketch hand-writing the order they run in.
+//!
+//! Within a stage systems run in the order they were added, unless moved by before and after constraints. A system can
+//! also have a run condition, e.g. to run every few passes. Every system run is timed with the context's profiler.
+//!
+//! Work which must happen at the start of every pass, before any system, such as emptying the event queues, is added as a
+//! hook with Schedule::at_start rather than as a system, so it doesn't depend on where it's ordered among the others.
+
+use std::fmt;
+use std::time::Instant;
+use crate::canvas::Canvas;
+use crate::sketch::Context;
+
+/// The stages of a pass, in the order they run
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+	/// Reacting to input, run from Sketch::input so it runs even while paused
+	Input,
+	/// Setting the forces and accelerations acting on entities
+	Forces,
+	/// Applying accelerations to velocities and velocities to positions
+	Integrate,
+	/// Finding and resolving collisions between entities
+	Collide,
+	/// Keeping entities within bounds and limits
+	Constrain,
+	/// Drawing, run from Sketch::draw
+	Render,
+	/// Removing and replacing entities at the end of the pass
+	Cleanup,
+}
+
+impl Stage {
+	/// Every stage, in the order they run
+	pub const ALL: [Stage; 7] = [ Stage::Input, Stage::Forces, Stage::Integrate, Stage::Collide, Stage::Constrain, Stage::Render, Stage::Cleanup ];
+}
+
+/// When a system runs
+#[derive(Copy, Clone, Debug)]
+pub enum Condition {
+	Always,
+	/// On passes which are a multiple of n, including the first
+	EveryNFrames( usize ),
+	/// Only while the sketch is paused. Only systems in the input and render stages run while paused
+	WhenPaused,
+	/// Only while the sketch isn't paused
+	WhenRunning,
+	/// Whenever the function returns true
+	If( fn( &Context ) -> bool ),
+}
+
+impl Condition {
+	/// Whether a system with this condition should run
+	pub fn check( &self, ctx: &Context ) -> bool {
+		match *self {
+			Condition::Always => true,
+			Condition::EveryNFrames( n ) => ctx.frame.is_multiple_of( n.max( 1 ) ),
+			Condition::WhenPaused => ctx.paused,
+			Condition::WhenRunning => !ctx.paused,
+			Condition::If( condition ) => condition( ctx ),
+		}
+	}
+}
+
+/// A system which changes the state
+pub type UpdateFn<S> = Box<dyn FnMut( &mut S, &mut Context )>;
+/// A system which draws the state
+pub type RenderFn<S> = Box<dyn Fn( &S, &mut dyn Canvas, &Context )>;
+
+enum Run<S> {
+	Update( UpdateFn<S> ),
+	Render( RenderFn<S> ),
+}
+
+/// A named system, with the stage it runs in, its ordering constraints and its run condition
+pub struct System<S> {
+	name: &'static str,
+	stage: Stage,
+	run: Run<S>,
+	before: Vec<&'static str>,
+	after: Vec<&'static str>,
+	condition: Condition,
+}
+
+impl<S> System<S> {
+	/// A system which changes the state in stage. Drawing systems are created with render instead
+	///
+	/// Panics if stage is the render stage.
+	pub fn new<F: FnMut( &mut S, &mut Context ) + 'static>( name: &'static str, stage: Stage, system: F ) -> System<S> {
+		assert!( stage != Stage::Render, "{} is in the render stage, so it must be created with System::render", name );
+		System { name, stage, run: Run::Update( Box::new( system ) ), before: Vec::new(), after: Vec::new(), condition: Condition::Always }
+	}
+
+	/// A system which draws the state, in the render stage
+	pub fn render<F: Fn( &S, &mut dyn Canvas, &Context ) + 'static>( name: &'static str, system: F ) -> System<S> {
+		System { name, stage: Stage::Render, run: Run::Render( Box::new( system ) ), before: Vec::new(), after: Vec::new(), condition: Condition::Always }
+	}
+
+	/// Runs this system before the system called name
+	pub fn before( mut self, name: &'static str ) -> System<S> {
+		self.before.push( name );
+		self
+	}
+
+	/// Runs this system after the system called name
+	pub fn after( mut self, name: &'static str ) -> System<S> {
+		self.after.push( name );
+		self
+	}
+
+	/// Only runs this system when condition holds
+	pub fn run_if( mut self, condition: Condition ) -> System<S> {
+		self.condition = condition;
+		self
+	}
+
+	pub fn name( &self ) -> &'static str {
+		self.name
+	}
+
+	pub fn stage( &self ) -> Stage {
+		self.stage
+	}
+}
+
+/// Why a schedule couldn't be built
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+	/// Two systems have the same name
+	Duplicate( &'static str ),
+	/// A system is ordered against a system which isn't in the schedule
+	Unknown { system: &'static str, other: &'static str },
+	/// A system has to run before a system in an earlier stage
+	WrongStage { first: &'static str, then: &'static str },
+	/// The ordering constraints between these systems form a loop
+	Cycle( Vec<&'static str> ),
+}
+
+impl fmt::Display for ScheduleError {
+	fn fmt( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+		match self {
+			ScheduleError::Duplicate( name ) => write!( f, "more than one system is called {}", name ),
+			ScheduleError::Unknown { system, other } => write!( f, "{} is ordered against {}, which isn't in the schedule", system, other ),
+			ScheduleError::WrongStage { first, then } => write!( f, "{} must run before {}, but is in a later stage", first, then ),
+			ScheduleError::Cycle( names ) => write!( f, "the ordering of {} forms a loop", names.join( ", " ) ),
+		}
+	}
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Systems over a state S, sorted into the order they run
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::rng::SimRng;
+/// use rusty_nature_of_code::schedule::*;
+/// use rusty_nature_of_code::sketch::Context;
+///
+/// let mut schedule = Schedule::new();
+/// schedule
+///     .add( System::new( "drop", Stage::Cleanup, |log: &mut Vec<&str>, _ctx| log.push( "drop" ) ) )
+///     .add( System::new( "collision", Stage::Collide, |log: &mut Vec<&str>, _ctx| log.push( "collision" ) ) )
+///     .add( System::new( "movement", Stage::Integrate, |log: &mut Vec<&str>, _ctx| log.push( "movement" ) ) )
+///     .add( System::new( "acceleration", Stage::Integrate, |log: &mut Vec<&str>, _ctx| log.push( "acceleration" ) ).before( "movement" ) )
+///     .add( System::new( "census", Stage::Cleanup, |log: &mut Vec<&str>, _ctx| log.push( "census" ) ).run_if( Condition::EveryNFrames( 2 ) ) );
+/// schedule.build().unwrap();
+///
+/// let mut log = Vec::new();
+/// let mut ctx = Context::new( ( 640, 480 ), SimRng::new( 0 ) );
+/// schedule.update( &mut log, &mut ctx );
+/// assert_eq!( log, [ "acceleration", "movement", "collision", "drop", "census" ] );
+///
+/// log.clear();
+/// ctx.frame = 1;
+/// schedule.update( &mut log, &mut ctx );
+/// assert_eq!( log, [ "acceleration", "movement", "collision", "drop" ] );
+///
+/// /* A system can't be ordered before one in an earlier stage */
+/// schedule.add( System::new( "gravity", Stage::Constrain, |_, _| () ).before( "movement" ) );
+/// assert!( matches!( schedule.build(), Err( ScheduleError::WrongStage { .. } ) ) );
+/// ```
+///
+pub struct Schedule<S> {
+	systems: Vec<System<S>>,
+	/// Indices of the systems in the order they run, set by build
+	order: Vec<usize>,
+	/// Whether systems have been added since the last build
+	dirty: bool,
+	/// Run at the start of each pass, before any system
+	hooks: Vec<UpdateFn<S>>,
+	/// The pass input last ran the hooks for, so the update of the same pass doesn't run them again
+	started: Option<usize>,
+}
+
+impl<S> Default for Schedule<S> {
+	fn default() -> Self {
+		Schedule::new()
+	}
+}
+
+impl<S> Schedule<S> {
+	pub fn new() -> Schedule<S> {
+		Schedule { systems: Vec::new(), order: Vec::new(), dirty: false, hooks: Vec::new(), started: None }
+	}
+
+	/// Adds a hook run at the start of every pass, before the systems in the input stage, or before the update if the input
+	/// stage isn't run. Hooks run in the order they were added
+	///
+	/// While paused only the input stage runs, so the hooks run once for the paused pass, not again each time input is run.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use rusty_nature_of_code::rng::SimRng;
+	/// use rusty_nature_of_code::schedule::*;
+	/// use rusty_nature_of_code::sketch::Context;
+	///
+	/// let mut schedule = Schedule::new();
+	/// schedule
+	///     .at_start( |log: &mut Vec<&str>, _ctx| log.clear() )
+	///     .add( System::new( "key", Stage::Input, |log: &mut Vec<&str>, _ctx| log.push( "key" ) ) )
+	///     .add( System::new( "movement", Stage::Integrate, |log: &mut Vec<&str>, _ctx| log.push( "movement" ) ) );
+	/// schedule.build().unwrap();
+	///
+	/// let mut log = Vec::new();
+	/// let mut ctx = Context::new( ( 640, 480 ), SimRng::new( 0 ) );
+	/// schedule.input( &mut log, &mut ctx );
+	/// schedule.update( &mut log, &mut ctx );
+	/// assert_eq!( log, [ "key", "movement" ] );
+	///
+	/// /* A pass without input still starts by running the hooks */
+	/// ctx.frame = 1;
+	/// schedule.update( &mut log, &mut ctx );
+	/// assert_eq!( log, [ "movement" ] );
+	/// ```
+	///
+	pub fn at_start<F: FnMut( &mut S, &mut Context ) + 'static>( &mut self, hook: F ) -> &mut Schedule<S> {
+		self.hooks.push( Box::new( hook ) );
+		self
+	}
+
+	/// Adds a system. build must be called again before the schedule is run
+	pub fn add( &mut self, system: System<S> ) -> &mut Schedule<S> {
+		self.systems.push( system );
+		self.dirty = true;
+		self
+	}
+
+	/// Sorts the systems into the order they run: by stage, then by their ordering constraints, then in the order they
+	/// were added
+	pub fn build( &mut self ) -> Result<(), ScheduleError> {
+		let index = |name: &str| self.systems.iter().position( |system| system.name == name );
+
+		/* Edges from each system to the systems which must run after it */
+		let mut next = vec![ Vec::new(); self.systems.len() ];
+		let mut waiting = vec![ 0; self.systems.len() ];
+		for ( i, system ) in self.systems.iter().enumerate() {
+			if index( system.name ) != Some( i ) {
+				return Err( ScheduleError::Duplicate( system.name ) );
+			}
+			let before = system.before.iter().map( |other| ( i, *other, true ) );
+			let after = system.after.iter().map( |other| ( i, *other, false ) );
+			for ( i, other, is_before ) in before.chain( after ) {
+				let j = index( other ).ok_or( ScheduleError::Unknown { system: system.name, other } )?;
+				let ( first, then ) = if is_before { ( i, j ) } else { ( j, i ) };
+				if self.systems[first].stage > self.systems[then].stage {
+					return Err( ScheduleError::WrongStage { first: self.systems[first].name, then: self.systems[then].name } );
+				}
+				next[first].push( then );
+				waiting[then] += 1;
+			}
+		}
+
+		/* Repeatedly takes the earliest system, by stage then the order added, which isn't waiting on any others. A
+		   system only waits on systems in its own stage or earlier ones, so this never runs a stage ahead */
+		let mut order = Vec::with_capacity( self.systems.len() );
+		let mut done = vec![ false; self.systems.len() ];
+		while let Some( i ) = ( 0..self.systems.len() ).filter( |i| !done[*i] && waiting[*i] == 0 ).min_by_key( |i| ( self.systems[*i].stage, *i ) ) {
+			done[i] = true;
+			order.push( i );
+			for &j in next[i].iter() {
+				waiting[j] -= 1;
+			}
+		}
+		if order.len() < self.systems.len() {
+			let names = ( 0..self.systems.len() ).filter( |i| !done[*i] ).map( |i| self.systems[i].name ).collect();
+			return Err( ScheduleError::Cycle( names ) );
+		}
+
+		self.order = order;
+		self.dirty = false;
+		Ok(())
+	}
+
+	/// Names of the systems in the order they run
+	pub fn names( &self ) -> Vec<&'static str> {
+		self.order.iter().map( |i| self.systems[*i].name ).collect()
+	}
+
+	/// Runs the hooks if they haven't run for this pass yet, then the systems in the input stage
+	pub fn input( &mut self, state: &mut S, ctx: &mut Context ) {
+		if self.started != Some( ctx.frame ) {
+			self.started = Some( ctx.frame );
+			self.start( state, ctx );
+		}
+		self.run( state, ctx, |stage| stage == Stage::Input );
+	}
+
+	/// Runs the hooks unless input already ran them for this pass, then the systems in every stage but the input and render
+	/// stages
+	pub fn update( &mut self, state: &mut S, ctx: &mut Context ) {
+		if self.started.take() != Some( ctx.frame ) {
+			self.start( state, ctx );
+		}
+		self.run( state, ctx, |stage| stage != Stage::Input && stage != Stage::Render );
+	}
+
+	/// Runs the systems in the render stage
+	pub fn draw( &self, state: &S, canvas: &mut dyn Canvas, ctx: &Context ) {
+		self.check_built();
+		for system in self.order.iter().map( |i| &self.systems[*i] ) {
+			if let Run::Render( render ) = &system.run {
+				if system.condition.check( ctx ) {
+					let start = Instant::now();
+					render( state, canvas, ctx );
+					ctx.profiler.record( system.name, start );
+				}
+			}
+		}
+	}
+
+	fn start( &mut self, state: &mut S, ctx: &mut Context ) {
+		for hook in self.hooks.iter_mut() {
+			hook( state, ctx );
+		}
+	}
+
+	fn run<F: Fn( Stage ) -> bool>( &mut self, state: &mut S, ctx: &mut Context, stages: F ) {
+		self.check_built();
+		for i in self.order.iter() {
+			let system = &mut self.systems[*i];
+			if let Run::Update( update ) = &mut system.run {
+				if stages( system.stage ) && system.condition.check( ctx ) {
+					let start = Instant::now();
+					update( state, ctx );
+					ctx.profiler.record( system.name, start );
+				}
+			}
+		}
+	}
+
+	fn check_built( &self ) {
+		assert!( !self.dirty, "Schedule::build must be called after adding systems" );
+	}
+}
@@ -0,0 +1,290 @@
+//! Shapes of bodies which move: circles, boxes and convex polygons, turned by the body's angle.
+//!
+//! Two shapes collide when there's no separating axis between them, a direction they can be projected onto without their
+//! projections overlapping. For polygons, the only directions worth checking are the normals of their edges, so each is
+//! tried in turn and the one they overlap least along gives the collision normal and depth. The contact points are then
+//! found by clipping the edge of one shape which faces the other against the sides of the other's edge, leaving the one or
+//! two points where they touch.
+
+use miscmath::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::collider::{self, Collider};
+use crate::component;
+
+/// The shape of a body, relative to its position and before it's turned by its angle
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::shape::*;
+///
+/// /* A box lying flat on a wider box, its bottom edge sunk 1.0 into the top of the other */
+/// let block = Shape::Rect { width: 20.0, height: 10.0 };
+/// let floor = Shape::Rect { width: 100.0, height: 10.0 };
+/// let manifold = collide( &floor, &Vec2::new( &0.0, &0.0 ), 0.0, &block, &Vec2::new( &0.0, &9.0 ), 0.0 ).unwrap();
+///
+/// /* The normal points from the floor up to the box, and it touches at both bottom corners */
+/// assert_eq!( manifold.normal, Vec2::new( &0.0, &1.0 ) );
+/// assert!( ( manifold.depth - 1.0 ).abs() < 0.0001 );
+/// assert_eq!( manifold.points.len(), 2 );
+///
+/// /* Turned 45 degrees, the box only touches at its bottom corner, and is clear of the floor if raised */
+/// let turned = collide( &floor, &Vec2::new( &0.0, &0.0 ), 0.0, &block, &Vec2::new( &0.0, &12.0 ), std::f32::consts::FRAC_PI_4 ).unwrap();
+/// assert_eq!( turned.points.len(), 1 );
+/// assert_eq!( collide( &floor, &Vec2::new( &0.0, &0.0 ), 0.0, &block, &Vec2::new( &0.0, &20.0 ), std::f32::consts::FRAC_PI_4 ), None );
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Shape {
+	/// A circle of radius around the body's position
+	Circle { radius: f32 },
+	/// A box width wide and height tall, centred on the body's position
+	Rect { width: f32, height: f32 },
+	/// A convex polygon around the body's position, with its points in anticlockwise order
+	Polygon( #[serde(with = "crate::snapshot::vec2s")] Vec<Vec2> ),
+}
+
+component!(
+	/// Shape of a body. Entities without one are circles of their attributes' radius
+	Shp => Shape,
+);
+
+/// Where two shapes touch
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifold {
+	/// Unit vector from the first shape towards the second, along which they should be pushed apart
+	pub normal: Vec2,
+	/// How far the shapes overlap along the normal
+	pub depth: f32,
+	/// The one or two points in world coordinates where the shapes touch
+	pub points: Vec<Vec2>,
+}
+
+impl Manifold {
+	/// The middle of the contact points, where the impulse between the shapes acts, or None if there are none
+	pub fn centre( &self ) -> Option<Vec2> {
+		let count = self.points.len() as f32;
+		( count > 0.0 ).then( || Vec2::new( &( self.points.iter().map( |p| p.x ).sum::<f32>() / count ), &( self.points.iter().map( |p| p.y ).sum::<f32>() / count ) ) )
+	}
+}
+
+impl Shape {
+	/// A convex polygon from points in either order around it
+	pub fn polygon( mut points: Vec<Vec2> ) -> Shape {
+		if collider::area( &points ) < 0.0 {
+			points.reverse();
+		}
+		Shape::Polygon( points )
+	}
+
+	/// Radius of the smallest circle around the body's position which holds the shape, whatever its angle
+	pub fn radius( &self ) -> f32 {
+		match self {
+			Shape::Circle { radius } => *radius,
+			Shape::Rect { width, height } => ( width * width + height * height ).sqrt() / 2.0,
+			Shape::Polygon( points ) => points.iter().map( |p| ( p.x * p.x + p.y * p.y ).sqrt() ).fold( 0.0, f32::max ),
+		}
+	}
+
+	/// The corners of a box or polygon in world coordinates, in anticlockwise order, for a body at pos turned by angle.
+	/// Circles have none
+	pub fn points( &self, pos: &Vec2, angle: f32 ) -> Vec<Vec2> {
+		let ( sin, cos ) = angle.sin_cos();
+		let place = |x: f32, y: f32| Vec2::new( &( pos.x + x * cos - y * sin ), &( pos.y + x * sin + y * cos ) );
+		match self {
+			Shape::Circle { .. } => Vec::new(),
+			Shape::Rect { width, height } => {
+				let ( x, y ) = ( width / 2.0, height / 2.0 );
+				vec![ place( -x, -y ), place( x, -y ), place( x, y ), place( -x, y ) ]
+			}
+			Shape::Polygon( points ) => points.iter().map( |p| place( p.x, p.y ) ).collect(),
+		}
+	}
+
+	/// How far the shape reaches from the body's position in direction, a unit vector, when turned by angle
+	pub fn extent( &self, angle: f32, direction: &Vec2 ) -> f32 {
+		match self {
+			Shape::Circle { radius } => *radius,
+			shape => shape.points( &Vec2::default(), angle ).iter().map( |p| p.x * direction.x + p.y * direction.y ).fold( f32::MIN, f32::max ),
+		}
+	}
+
+	/// The point of the shape furthest in direction, a unit vector, for a body at pos turned by angle. When a whole side
+	/// faces that way, it's the middle of the side
+	pub fn support( &self, pos: &Vec2, angle: f32, direction: &Vec2 ) -> Vec2 {
+		let points = self.points( pos, angle );
+		if points.is_empty() {
+			return along( pos, direction, self.extent( angle, direction ) );
+		}
+
+		/* Corners within a small tolerance of the furthest count as level with it, so a side resting flat against a wall
+		   touches it in the middle rather than at whichever corner happens to be further by a rounding error */
+		let reach = |p: &Vec2| p.x * direction.x + p.y * direction.y;
+		let furthest = points.iter().map( reach ).fold( f32::MIN, f32::max );
+		let level: Vec<&Vec2> = points.iter().filter( |p| reach( p ) >= furthest - 0.01 * self.radius() ).collect();
+		let count = level.len() as f32;
+		Vec2::new( &( level.iter().map( |p| p.x ).sum::<f32>() / count ), &( level.iter().map( |p| p.y ).sum::<f32>() / count ) )
+	}
+
+	/// Moment of inertia of the shape about the body's position, for a body of mass spread evenly over it. The further
+	/// the mass is from the centre, the harder the body is to spin
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use miscmath::prelude::*;
+	/// use rusty_nature_of_code::shape::*;
+	///
+	/// /* A square polygon spins just like a box of the same size */
+	/// let square = Shape::polygon( vec![ Vec2::new( &-1.0, &-1.0 ), Vec2::new( &1.0, &-1.0 ), Vec2::new( &1.0, &1.0 ), Vec2::new( &-1.0, &1.0 ) ] );
+	/// let rect = Shape::Rect { width: 2.0, height: 2.0 };
+	/// assert!( ( square.inertia( 3.0 ) - rect.inertia( 3.0 ) ).abs() < 0.0001 );
+	/// assert_eq!( rect.inertia( 3.0 ), 2.0 );
+	/// ```
+	///
+	pub fn inertia( &self, mass: f32 ) -> f32 {
+		match self {
+			Shape::Circle { radius } => mass * radius * radius / 2.0,
+			Shape::Rect { width, height } => mass * ( width * width + height * height ) / 12.0,
+			Shape::Polygon( points ) => {
+				/* Sums the inertia of the triangles from the body's position to each edge, weighted by their areas */
+				let ( weighted, area ) = collider::edges( points ).fold( ( 0.0, 0.0 ), |( weighted, area ), ( a, b )| {
+					let cross = a.x * b.y - b.x * a.y;
+					( weighted + cross * ( a.x * a.x + a.y * a.y + a.x * b.x + a.y * b.y + b.x * b.x + b.y * b.y ), area + cross )
+				});
+				mass * weighted / ( 6.0 * area )
+			}
+		}
+	}
+
+	/// Whether every value is finite, circles and boxes have sizes greater than 0, and polygons have at least 3 points and
+	/// are convex and anticlockwise
+	pub fn is_valid( &self ) -> bool {
+		match self {
+			Shape::Circle { radius } => radius.is_finite() && *radius > 0.0,
+			Shape::Rect { width, height } => width.is_finite() && height.is_finite() && *width > 0.0 && *height > 0.0,
+			Shape::Polygon( points ) => Collider::Polygon( points.clone() ).is_valid(),
+		}
+	}
+}
+
+/// The contact between shape a, at pos_a turned by angle_a, and shape b, at pos_b turned by angle_b, or None if they
+/// aren't touching
+pub fn collide( a: &Shape, pos_a: &Vec2, angle_a: f32, b: &Shape, pos_b: &Vec2, angle_b: f32 ) -> Option<Manifold> {
+	match ( a, b ) {
+		( Shape::Circle { radius: radius_a }, Shape::Circle { radius: radius_b } ) => {
+			/* Circles touch at the point between their edges, along the line between their centres */
+			let contact = Collider::Circle { pos: *pos_a, radius: *radius_a }.contact( pos_b, *radius_b )?;
+			Some( Manifold { points: vec![ along( pos_b, &contact.normal, contact.depth / 2.0 - radius_b ) ], normal: contact.normal, depth: contact.depth } )
+		}
+		( Shape::Circle { radius }, polygon ) => {
+			/* The contact from the polygon's side points towards the circle, the opposite way to the manifold's normal */
+			let contact = Collider::Polygon( polygon.points( pos_b, angle_b ) ).contact( pos_a, *radius )?;
+			let normal = Vec2::new( &-contact.normal.x, &-contact.normal.y );
+			Some( Manifold { points: vec![ along( pos_a, &normal, radius - contact.depth / 2.0 ) ], normal, depth: contact.depth } )
+		}
+		( _, Shape::Circle { .. } ) => collide( b, pos_b, angle_b, a, pos_a, angle_a ).map( |manifold| Manifold {
+			normal: Vec2::new( &-manifold.normal.x, &-manifold.normal.y ),
+			..manifold
+		}),
+		_ => polygons( &a.points( pos_a, angle_a ), &b.points( pos_b, angle_b ) ),
+	}
+}
+
+/// The contact between two convex polygons, with their points in anticlockwise order. Segments, polygons with 2 points,
+/// work too, which is how shapes collide with segment obstacles
+pub fn polygons( a: &[Vec2], b: &[Vec2] ) -> Option<Manifold> {
+	/* Checks every edge normal of both polygons for a separating axis, keeping the one they overlap least along */
+	let ( separation_a, edge_a ) = max_separation( a, b )?;
+	if separation_a > 0.0 {
+		return None;
+	}
+	let ( separation_b, edge_b ) = max_separation( b, a )?;
+	if separation_b > 0.0 {
+		return None;
+	}
+
+	/* The reference edge is the one with the least overlap, preferring a's so near ties don't flicker between them. The
+	   incident edge is the edge of the other polygon facing most against it */
+	let ( reference, incident, edge, flip ) = match separation_b > separation_a + 0.0005 {
+		true => ( b, a, edge_b, true ),
+		false => ( a, b, edge_a, false ),
+	};
+	let ( v1, v2 ) = ( reference[edge], reference[( edge + 1 ) % reference.len()] );
+	let normal = outward( &v1, &v2 )?;
+	let ( i1, i2 ) = collider::edges( incident )
+		.min_by( |( a1, a2 ), ( b1, b2 )| {
+			let facing = |p: &Vec2, q: &Vec2| outward( p, q ).map_or( f32::MAX, |n| n.x * normal.x + n.y * normal.y );
+			facing( a1, a2 ).total_cmp( &facing( b1, b2 ) )
+		})?;
+
+	/* Clips the incident edge to between the ends of the reference edge, then keeps the points which are inside it */
+	let tangent = Vec2::new( &( v2.x - v1.x ), &( v2.y - v1.y ) );
+	let dot = |p: &Vec2, q: &Vec2| p.x * q.x + p.y * q.y;
+	let clipped = clip( [*i1, *i2], &tangent, dot( &tangent, &v1 ) )?;
+	let backwards = Vec2::new( &-tangent.x, &-tangent.y );
+	let clipped = clip( clipped, &backwards, dot( &backwards, &v2 ) )?;
+
+	let mut depth = 0.0f32;
+	let points: Vec<Vec2> = clipped.iter().filter_map( |p| {
+		let separation = dot( &Vec2::new( &( p.x - v1.x ), &( p.y - v1.y ) ), &normal );
+		( separation <= 0.0 ).then( || {
+			depth = depth.max( -separation );
+			/* The contact is halfway between the incident point and the reference edge */
+			along( p, &normal, -separation / 2.0 )
+		})
+	}).collect();
+	if points.is_empty() {
+		return None;
+	}
+
+	let normal = if flip { Vec2::new( &-normal.x, &-normal.y ) } else { normal };
+	Some( Manifold { normal, depth, points } )
+}
+
+/// The shape and position of an obstacle's collider, so shapes can collide with it. Boxes, polygons and segments are
+/// polygons around the origin, which collide the same as their collider
+pub fn obstacle_shape( collider: &Collider ) -> ( Shape, Vec2 ) {
+	match collider {
+		Collider::Circle { pos, radius } => ( Shape::Circle { radius: *radius }, *pos ),
+		collider => ( Shape::Polygon( collider.points() ), Vec2::default() ),
+	}
+}
+
+/// The largest distance b's points are outside any edge of a, with that edge's index, or None if a has no edges
+fn max_separation( a: &[Vec2], b: &[Vec2] ) -> Option<( f32, usize )> {
+	collider::edges( a ).enumerate().filter_map( |( i, ( v1, v2 ) )| {
+		let normal = outward( v1, v2 )?;
+		let deepest = b.iter().map( |p| ( p.x - v1.x ) * normal.x + ( p.y - v1.y ) * normal.y ).fold( f32::MAX, f32::min );
+		Some( ( deepest, i ) )
+	}).max_by( |a, b| a.0.total_cmp( &b.0 ) )
+}
+
+/// The outward unit normal of the edge from v1 to v2 of an anticlockwise polygon
+fn outward( v1: &Vec2, v2: &Vec2 ) -> Option<Vec2> {
+	collider::unit( &Vec2::new( &( v2.y - v1.y ), &( v1.x - v2.x ) ) )
+}
+
+/// pos moved distance along direction
+fn along( pos: &Vec2, direction: &Vec2, distance: f32 ) -> Vec2 {
+	Vec2::new( &( pos.x + direction.x * distance ), &( pos.y + direction.y * distance ) )
+}
+
+/// The part of the segment between points on the side of the line where a point p has dot( p, direction ) >= offset, or
+/// None if it's all on the other side
+fn clip( points: [Vec2; 2], direction: &Vec2, offset: f32 ) -> Option<[Vec2; 2]> {
+	let [p1, p2] = points;
+	let ( d1, d2 ) = ( p1.x * direction.x + p1.y * direction.y - offset, p2.x * direction.x + p2.y * direction.y - offset );
+	match ( d1 >= 0.0, d2 >= 0.0 ) {
+		( true, true ) => Some( points ),
+		( false, false ) => None,
+		/* The segment crosses the line, so the point outside is replaced with where it crosses */
+		( inside_1, _ ) => {
+			let t = d1 / ( d1 - d2 );
+			let crossing = Vec2::new( &( p1.x + ( p2.x - p1.x ) * t ), &( p1.y + ( p2.y - p1.y ) * t ) );
+			Some( if inside_1 { [p1, crossing] } else { [crossing, p2] } )
+		}
+	}
+}
@@ -0,0 +1,93 @@
+//! A gallery menu listing every registered sketch, which runs the chosen one in the same window
+
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::config::Config;
+use super::registry::{self, Registration};
+use super::runner::{input, run_in, window};
+use super::Key;
+
+/// Key which leaves a running sketch and returns to the menu, or quits from the menu
+pub const BACK_KEY: Key = Key::Escape;
+
+/// Height of each entry in the menu in pixels
+const ENTRY_HEIGHT: f32 = 40.0;
+/// Distance from the top of the window to the first entry
+const MENU_TOP: f32 = 80.0;
+
+/// Draws the menu with the selected entry highlighted
+pub fn draw_menu( canvas: &mut dyn Canvas, sketches: &[&Registration], selected: usize ) {
+	canvas.clear( Rgba::BLACK );
+	canvas.text( "The Nature of Code", Vec2::new( &20.0, &20.0 ), 30.0, Rgba::WHITE );
+
+	let width = canvas.size().0;
+	for ( i, sketch ) in sketches.iter().enumerate() {
+		let top = MENU_TOP + ENTRY_HEIGHT * i as f32;
+		if i == selected {
+			canvas.rect( Vec2::new( &10.0, &( top - 6.0 ) ), Vec2::new( &( width - 20.0 ), &ENTRY_HEIGHT ), Rgba::new( 60, 60, 60, 255 ) );
+		}
+		canvas.text( &format!( "{}. {}", i + 1, sketch.name ), Vec2::new( &20.0, &top ), 16.0, Rgba::WHITE );
+		canvas.text( sketch.description, Vec2::new( &40.0, &( top + 18.0 ) ), 10.0, Rgba::new( 180, 180, 180, 255 ) );
+	}
+
+	let help = "Up/Down and Enter, a number, or click to run. Esc returns here";
+	canvas.text( help, Vec2::new( &20.0, &( canvas.size().1 - 24.0 ) ), 10.0, Rgba::new( 180, 180, 180, 255 ) );
+}
+
+/// Opens a window showing the menu, and runs sketches from it until the window is closed or Esc is pressed in the menu
+///
+/// Every sketch is run with config. If it has no seed, each sketch gets a new random seed every time it's started.
+pub fn launch( config: Config ) {
+	let sketches = registry::sketches();
+	let ( mut rl, thread ) = window( "The Nature of Code", ( 640, 480 ) );
+	/* Esc is used to get back to the menu, so it mustn't close the window */
+	rl.set_exit_key( None );
+	let mut selected: usize = 0;
+
+	'_menu_loop: while !rl.window_should_close() {
+		let input = input( &mut rl );
+		let mut chosen = None;
+		for key in input.keys.iter() {
+			match key {
+				Key::Up => selected = selected.saturating_sub( 1 ),
+				Key::Down => selected = ( selected + 1 ).min( sketches.len().saturating_sub( 1 ) ),
+				Key::Enter | Key::Space => chosen = Some( selected ),
+				Key::Char( c ) => if let Some( n ) = c.to_digit( 10 ) {
+					if ( 1..=sketches.len() ).contains( &( n as usize ) ) {
+						chosen = Some( n as usize - 1 );
+					}
+				},
+				_ => (),
+			}
+		}
+		if input.pressed( BACK_KEY ) {
+			break;
+		}
+		if input.mouse_pressed {
+			let i = ( ( input.mouse.y - MENU_TOP + 6.0 ) / ENTRY_HEIGHT ).floor();
+			if i >= 0.0 && ( i as usize ) < sketches.len() {
+				chosen = Some( i as usize );
+			}
+		}
+
+		if let Some( i ) = chosen {
+			selected = i;
+			let mut sketch = ( sketches[i].create )();
+			let ( width, height ) = config.size( sketch.size() );
+			rl.set_window_size( width, height );
+			rl.set_window_title( &thread, sketch.title() );
+
+			/* Stops the launcher when the window is closed rather than Esc being pressed */
+			if !run_in( &mut rl, &thread, sketch.as_mut(), config.clone(), Some( BACK_KEY ) ) {
+				break;
+			}
+			rl.set_window_size( 640, 480 );
+			rl.set_window_title( &thread, "The Nature of Code" );
+			continue;
+		}
+
+		let mut display = rl.begin_drawing( &thread );
+		draw_menu( &mut display, &sketches, selected );
+	}
+}
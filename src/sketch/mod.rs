@@ -0,0 +1,245 @@
+//! The shape every sketch follows: set up once, then update and draw each pass. A runner owns the window, timing, HUD,
+//! pause and screenshot keys, so a sketch only holds its own state and systems.
+
+use std::io;
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::config::Config;
+use crate::profile::Profiler;
+use crate::rng::SimRng;
+
+pub mod registry;
+pub mod time;
+#[cfg(feature = "render")]
+pub mod runner;
+#[cfg(feature = "render")]
+pub mod launcher;
+
+#[cfg(feature = "render")]
+pub use runner::run;
+
+/// State the runner shares with a sketch each pass
+#[derive(Clone, Debug)]
+pub struct Context {
+	/// Current width and height of the window or canvas in pixels
+	pub screen_size: ( i32, i32 ),
+	/// Number of passes the sketch has been updated for
+	pub frame: usize,
+	/// Frames per second the runner is achieving, 0 when it isn't measured, e.g. when capturing headless
+	pub fps: u32,
+	/// Whether updates are paused. Paused sketches are still drawn
+	pub paused: bool,
+	/// Whether the sketch's debug overlay is drawn, see Sketch::debug
+	pub debug: bool,
+	/// Updates run per pass, below 1 some passes run no update. See time::TIME_SCALES
+	pub time_scale: f32,
+	/// Fraction of an update carried over to the next pass when the time scale isn't whole
+	carry: f32,
+	/// The random number generator every system and spawn in the sketch should use
+	pub rng: SimRng,
+	/// Parameters read from the command line and config file
+	pub config: Config,
+	/// Times the sketch's systems, see profile
+	pub profiler: Profiler,
+}
+
+impl Context {
+	/// Creates a context for a sketch which hasn't been updated yet
+	pub fn new( screen_size: ( i32, i32 ), rng: SimRng ) -> Context {
+		Context { screen_size, frame: 0, fps: 0, paused: false, debug: false, time_scale: 1.0, carry: 0.0, rng, config: Config::default(), profiler: Profiler::new() }
+	}
+
+	/// Creates a context with the parameters in config, and a random number generator seeded from it
+	pub fn with_config( screen_size: ( i32, i32 ), config: Config ) -> Context {
+		Context { config: config.clone(), ..Context::new( screen_size, config.rng() ) }
+	}
+
+	/// The screen size converted to floats, for use in calculations
+	pub fn size( &self ) -> ( f32, f32 ) {
+		( self.screen_size.0 as f32, self.screen_size.1 as f32 )
+	}
+}
+
+/// A key press, independent of the renderer. Letters are always lowercase
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+	Char( char ),
+	Space,
+	Enter,
+	Escape,
+	Backspace,
+	Tab,
+	Up,
+	Down,
+	Left,
+	Right,
+	/// A function key, F1 is F( 1 )
+	F( u8 ),
+}
+
+/// Input gathered by the runner for the current pass
+#[derive(Clone, Debug, Default)]
+pub struct Input {
+	/// Keys pressed since the last pass, in the order they were pressed
+	pub keys: Vec<Key>,
+	/// Mouse position in screen coordinates
+	pub mouse: Vec2,
+	/// Whether the left mouse button is held down
+	pub mouse_down: bool,
+	/// Whether the left mouse button was pressed this pass
+	pub mouse_pressed: bool,
+}
+
+impl Input {
+	/// Whether key was pressed this pass
+	pub fn pressed( &self, key: Key ) -> bool {
+		self.keys.contains( &key )
+	}
+}
+
+/// A Nature of Code example, which can be run in a window by a runner or drawn headless onto any Canvas
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::canvas::*;
+/// use rusty_nature_of_code::color::Rgba;
+/// use rusty_nature_of_code::rng::SimRng;
+/// use rusty_nature_of_code::sketch::*;
+///
+/// struct Faller { pos: Vec2 }
+///
+/// impl Sketch for Faller {
+///     fn title( &self ) -> &str { "faller" }
+///
+///     fn update( &mut self, _ctx: &mut Context ) { self.pos.y -= 1.0; }
+///
+///     fn draw( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+///         canvas.circle( canvas.flip( &self.pos ), 5.0, Rgba::WHITE );
+///     }
+/// }
+///
+/// let mut sketch = Faller { pos: Vec2::new( &10.0, &100.0 ) };
+/// let mut ctx = Context::new( ( 640, 480 ), SimRng::new( 0 ) );
+/// let mut canvas = RecordingCanvas::new( 640.0, 480.0 );
+/// step( &mut sketch, &mut ctx, &mut canvas, false );
+///
+/// assert_eq!( ctx.frame, 1 );
+/// assert_eq!( canvas.commands()[1], DrawCommand::Circle { center: Vec2::new( &10.0, &381.0 ), radius: 5.0, color: Rgba::WHITE } );
+/// ```
+///
+pub trait Sketch {
+	/// Title of the window the sketch is run in
+	fn title( &self ) -> &str;
+
+	/// Initial width and height of the window
+	fn size( &self ) -> ( i32, i32 ) {
+		( 640, 480 )
+	}
+
+	/// Colour the screen is cleared to before each draw. None leaves everything drawn on screen, so sketches can leave trails
+	fn background( &self ) -> Option<Rgba> {
+		Some( Rgba::BLACK )
+	}
+
+	/// Runs once before the first update
+	fn setup( &mut self, _ctx: &mut Context ) {}
+
+	/// Advances the sketch by one pass
+	fn update( &mut self, ctx: &mut Context );
+
+	/// Draws the current state of the sketch
+	fn draw( &self, canvas: &mut dyn Canvas, ctx: &Context );
+
+	/// Draws over the sketch what its systems see, e.g. with the systems in debug. Only called while ctx.debug is set
+	fn debug( &self, _canvas: &mut dyn Canvas, _ctx: &Context ) {}
+
+	/// Handles the input gathered for this pass, before update is called. Runs even while paused
+	fn input( &mut self, _input: &Input, _ctx: &mut Context ) {}
+
+	/// Extra lines of text the HUD shows under the pass and FPS
+	fn hud( &self, _ctx: &Context ) -> Vec<String> {
+		Vec::new()
+	}
+
+	/// The sketch's state encoded as bytes, for recording, replaying and rewinding. None if the sketch can't be saved
+	fn save( &self ) -> Option<Vec<u8>> {
+		None
+	}
+
+	/// Restores state written by save
+	fn load( &mut self, _state: &[u8] ) -> io::Result<()> {
+		Err( io::Error::new( io::ErrorKind::Unsupported, format!( "{} can't be restored", self.title() ) ) )
+	}
+}
+
+/// Runs one pass of a sketch: updates it unless paused, clears the background, draws it and its debug overlay if ctx.debug
+/// is set, then draws the HUD if hud is true
+///
+/// The sketch is updated ctx.time_scale times on average, so slow motion skips updates on some passes and fast forward runs
+/// several in one pass.
+pub fn step( sketch: &mut dyn Sketch, ctx: &mut Context, canvas: &mut dyn Canvas, hud: bool ) {
+	let mut updates = 0;
+	if !ctx.paused {
+		ctx.carry += ctx.time_scale;
+		while ctx.carry >= 1.0 && !ctx.paused {
+			ctx.carry -= 1.0;
+			/* Every update but the last is counted here, the last is counted after drawing like a single update is */
+			if updates > 0 {
+				ctx.frame += 1;
+			}
+			sketch.update( ctx );
+			updates += 1;
+		}
+	}
+
+	match sketch.background() {
+		Some( color ) => canvas.clear( color ),
+		/* Clears the background for the first two passes only, as a window draws on two buffers which are swapped every pass.
+		   If only done once there will be severe flickering */
+		None => if ctx.frame < 2 { canvas.clear( Rgba::BLACK ) },
+	}
+	sketch.draw( canvas, ctx );
+	if ctx.debug {
+		sketch.debug( canvas, ctx );
+	}
+
+	if hud {
+		draw_hud( sketch, ctx, canvas );
+	}
+
+	if updates > 0 && !ctx.paused {
+		ctx.frame += 1;
+	}
+}
+
+/// Draws the pass counter, FPS and the sketch's own HUD lines in the top left of the canvas
+pub fn draw_hud( sketch: &dyn Sketch, ctx: &Context, canvas: &mut dyn Canvas ) {
+	let mut lines = vec![ format!( "Pass = {}", ctx.frame ) ];
+	if ctx.fps > 0 {
+		lines.push( format!( "FPS = {}", ctx.fps ) );
+	}
+	lines.extend( sketch.hud( ctx ) );
+	if ctx.time_scale != 1.0 {
+		lines.push( format!( "Speed = {}x", ctx.time_scale ) );
+	}
+	if ctx.paused {
+		lines.push( "Paused".to_string() );
+	}
+
+	/* Picks black text on light backgrounds and white text on dark ones. Sketches which leave trails get a black box behind
+	   the text, so the old text doesn't stay on screen */
+	let color = match sketch.background() {
+		Some( bg ) if bg.r as u32 + bg.g as u32 + bg.b as u32 > 382 => Rgba::BLACK,
+		Some( _ ) => Rgba::WHITE,
+		None => {
+			canvas.rect( Vec2::new( &0.0, &0.0 ), Vec2::new( &200.0, &( 20.0 * lines.len() as f32 + 16.0 ) ), Rgba::BLACK );
+			Rgba::WHITE
+		}
+	};
+	for ( i, line ) in lines.iter().enumerate() {
+		canvas.text( line, Vec2::new( &12.0, &( 12.0 + 20.0 * i as f32 ) ), 20.0, color );
+	}
+}
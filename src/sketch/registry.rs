@@ -0,0 +1,76 @@
+//! Every sketch registers itself here with register_sketch!, so launchers and tools can list and create them by name
+//! without keeping their own list.
+
+use super::Sketch;
+
+#[doc(hidden)]
+pub use inventory;
+
+/// A sketch which can be created by name
+pub struct Registration {
+	/// Name used on the command line, e.g. bouncy_balls
+	pub name: &'static str,
+	/// One line description shown in the menu
+	pub description: &'static str,
+	/// Creates a new instance of the sketch
+	pub create: fn() -> Box<dyn Sketch>,
+}
+
+inventory::collect!( Registration );
+
+/// Registers a sketch type, which must have a new() function, under a name
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::canvas::Canvas;
+/// use rusty_nature_of_code::register_sketch;
+/// use rusty_nature_of_code::sketch::{registry, Context, Sketch};
+///
+/// struct Empty;
+///
+/// impl Empty {
+///     fn new() -> Empty { Empty }
+/// }
+///
+/// impl Sketch for Empty {
+///     fn title( &self ) -> &str { "empty" }
+///     fn update( &mut self, _ctx: &mut Context ) {}
+///     fn draw( &self, _canvas: &mut dyn Canvas, _ctx: &Context ) {}
+/// }
+///
+/// register_sketch!( "empty", "Draws nothing", Empty );
+///
+/// assert_eq!( registry::find( "empty" ).unwrap().description, "Draws nothing" );
+/// assert!( registry::find( "bouncy_balls" ).is_some() );
+/// ```
+///
+#[macro_export]
+macro_rules! register_sketch {
+	( $name:expr, $description:expr, $sketch:ty ) => {
+		$crate::sketch::registry::inventory::submit! {
+			$crate::sketch::registry::Registration {
+				name: $name,
+				description: $description,
+				create: || Box::new( <$sketch>::new() ),
+			}
+		}
+	};
+}
+
+/// Every registered sketch, sorted by name
+pub fn sketches() -> Vec<&'static Registration> {
+	let mut sketches: Vec<&Registration> = inventory::iter::<Registration>.into_iter().collect();
+	sketches.sort_by_key( |sketch| sketch.name );
+	sketches
+}
+
+/// Finds the sketch registered under name
+pub fn find( name: &str ) -> Option<&'static Registration> {
+	inventory::iter::<Registration>.into_iter().find( |sketch| sketch.name == name )
+}
+
+/// The names of every registered sketch separated by '|', for usage messages
+pub fn names() -> String {
+	sketches().iter().map( |sketch| sketch.name ).collect::<Vec<_>>().join( "|" )
+}
@@ -0,0 +1,173 @@
+//! Runs a sketch in a raylib window
+
+use miscmath::prelude::*;
+use raylib::prelude::*;
+use crate::config::Config;
+use crate::profile::{draw_profile, TraceWriter};
+use crate::record::{Recorder, Recording, Replay};
+use super::time::TimeControls;
+use super::{step, Context, Input, Key, Sketch};
+
+/// Key which toggles the HUD
+pub const HUD_KEY: Key = Key::Char( 'h' );
+/// Key which toggles the sketch's debug overlay
+pub const DEBUG_KEY: Key = Key::Char( 'g' );
+/// Key which toggles the bar chart of system timings
+pub const PROFILE_KEY: Key = Key::Char( 't' );
+/// Key which saves a screenshot to the working directory
+pub const SCREENSHOT_KEY: Key = Key::F( 2 );
+
+/// Converts a raylib key code to a Key, raylib uses the ascii code for printable keys
+fn key( code: u32 ) -> Option<Key> {
+	match code {
+		32 => Some( Key::Space ),
+		33..=126 => char::from_u32( code ).map( |c| Key::Char( c.to_ascii_lowercase() ) ),
+		256 => Some( Key::Escape ),
+		257 => Some( Key::Enter ),
+		258 => Some( Key::Tab ),
+		259 => Some( Key::Backspace ),
+		262 => Some( Key::Right ),
+		263 => Some( Key::Left ),
+		264 => Some( Key::Down ),
+		265 => Some( Key::Up ),
+		290..=301 => Some( Key::F( ( code - 289 ) as u8 ) ),
+		_ => None,
+	}
+}
+
+/// Gathers the keys pressed and the mouse state since the last pass
+pub(crate) fn input( rl: &mut RaylibHandle ) -> Input {
+	let mut keys = Vec::new();
+	while let Some( code ) = rl.get_key_pressed_number() {
+		keys.extend( key( code ) );
+	}
+	let mouse = rl.get_mouse_position();
+	Input {
+		keys,
+		mouse: Vec2::new( &mouse.x, &mouse.y ),
+		mouse_down: rl.is_mouse_button_down( MouseButton::MOUSE_LEFT_BUTTON ),
+		mouse_pressed: rl.is_mouse_button_pressed( MouseButton::MOUSE_LEFT_BUTTON ),
+	}
+}
+
+/// Opens a window for the sketch and runs it at 60 fps until the window is closed
+///
+/// Parameters are read from the command line and the config file it names, see Config. The seed is printed so the run can
+/// be reproduced. P pauses, full stop steps once, comma rewinds, minus and equals slow down and speed up time, H toggles the
+/// HUD, G toggles the debug overlay, T toggles system timings and F2 saves a screenshot. See time for the time controls.
+pub fn run( sketch: &mut dyn Sketch ) {
+	run_with( sketch, Config::from_args() );
+}
+
+/// Runs the sketch like run does, with config in place of the one from the command line
+pub fn run_with( sketch: &mut dyn Sketch, config: Config ) {
+	let ( mut rl, thread ) = window( sketch.title(), config.size( sketch.size() ) );
+	run_in( &mut rl, &thread, sketch, config, None );
+}
+
+/// Creates the RayLib handle and thread, sets the screen size, and gives the window a title
+pub fn window( title: &str, size: ( i32, i32 ) ) -> ( RaylibHandle, RaylibThread ) {
+	let ( mut rl, thread ) = init()
+		.size( size.0, size.1 )
+		.title( title )
+		.resizable()
+		.msaa_4x()
+		.build();
+	rl.set_target_fps( 60 );
+	( rl, thread )
+}
+
+/// Runs the sketch in an already open window until the window is closed or back is pressed. Returns true if back was pressed
+///
+/// The sketch is recorded or replayed if config asks for it. If the recording can't be opened the error is printed and true
+/// is returned, going back to the launcher's menu.
+pub fn run_in( rl: &mut RaylibHandle, thread: &RaylibThread, sketch: &mut dyn Sketch, mut config: Config, back: Option<Key> ) -> bool {
+	let recording = match Recording::from_config( &mut config ) {
+		Ok( recording ) => recording,
+		Err( e ) => {
+			eprintln!( "failed to read recording: {}", e );
+			return true;
+		}
+	};
+	match recording.map( |recording| Replay::new( sketch, recording ) ) {
+		Some( Ok( mut replay ) ) => run_loop( rl, thread, &mut replay, config, back ),
+		Some( Err( e ) ) => {
+			eprintln!( "failed to replay: {}", e );
+			true
+		}
+		None => run_loop( rl, thread, sketch, config, back ),
+	}
+}
+
+/// Sets up the sketch then updates and draws it until the window is closed or back is pressed
+fn run_loop( rl: &mut RaylibHandle, thread: &RaylibThread, sketch: &mut dyn Sketch, config: Config, back: Option<Key> ) -> bool {
+	let mut ctx = Context::with_config( ( rl.get_screen_width(), rl.get_screen_height() ), config );
+	println!( "seed = {}", ctx.rng.seed() );
+	sketch.setup( &mut ctx );
+	let mut hud = true;
+	let mut time = TimeControls::default();
+	let mut profile = false;
+
+	let mut recorder = Recorder::from_config( sketch, &ctx ).unwrap_or_else( |e| {
+		eprintln!( "failed to start recording: {}", e );
+		None
+	});
+	let mut trace = ctx.config.profile.as_ref().and_then( |path| TraceWriter::create( path ).map_err( |e| {
+		eprintln!( "failed to create {}: {}", path.display(), e );
+	}).ok() );
+	let mut back_pressed = false;
+
+	/* Draw
+	   Loops until the user closes the window */
+	'_draw_loop: while !rl.window_should_close() {
+		/* Records the setup state first, then every pass the sketch is updated */
+		if let Some( Err( e ) ) = recorder.as_mut().map( |recorder| recorder.record( sketch, &ctx ) ) {
+			eprintln!( "stopped recording: {}", e );
+			recorder = None;
+		}
+		time.remember( sketch, &ctx );
+
+		let input = input( rl );
+		if back.is_some_and( |back| input.pressed( back ) ) {
+			back_pressed = true;
+			break;
+		}
+		time.input( &input, sketch, &mut ctx );
+		if input.pressed( HUD_KEY ) {
+			hud = !hud;
+		}
+		if input.pressed( DEBUG_KEY ) {
+			ctx.debug = !ctx.debug;
+		}
+		if input.pressed( PROFILE_KEY ) {
+			profile = !profile;
+		}
+		if input.pressed( SCREENSHOT_KEY ) {
+			let file = format!( "{}_{:05}.png", sketch.title().replace( ' ', "_" ), ctx.frame );
+			rl.take_screenshot( thread, &file );
+		}
+		sketch.input( &input, &mut ctx );
+
+		ctx.screen_size = ( rl.get_screen_width(), rl.get_screen_height() );
+		ctx.fps = rl.get_fps();
+
+		let pass = ctx.frame;
+		let mut display = rl.begin_drawing( thread );
+		step( sketch, &mut ctx, &mut display, hud );
+		if profile {
+			draw_profile( &mut display, &ctx.profiler );
+		}
+		drop( display );
+
+		let events = ctx.profiler.end_pass();
+		if let Some( Err( e ) ) = trace.as_mut().map( |trace| trace.write( pass, &events ) ) {
+			eprintln!( "stopped writing timings: {}", e );
+			trace = None;
+		}
+	}
+
+	if let Some( Err( e ) ) = trace.map( |trace| trace.finish() ) {
+		eprintln!( "failed to finish timings: {}", e );
+	}
+	back_pressed
+}
@@ -0,0 +1,148 @@
+//! Time controls shared by every sketch: pause, single step, slow motion, fast forward and rewind
+
+use std::collections::VecDeque;
+use crate::record::Frame;
+use super::{Context, Input, Key, Sketch};
+
+/// Key which pauses and resumes updates
+pub const PAUSE_KEY: Key = Key::Char( 'p' );
+/// Key which pauses and runs a single update
+pub const STEP_KEY: Key = Key::Char( '.' );
+/// Key which pauses and goes back one pass
+pub const REWIND_KEY: Key = Key::Char( ',' );
+/// Key which moves to the next slower time scale
+pub const SLOWER_KEY: Key = Key::Char( '-' );
+/// Key which moves to the next faster time scale
+pub const FASTER_KEY: Key = Key::Char( '=' );
+
+/// Time scales the slower and faster keys move between
+pub const TIME_SCALES: [f32; 6] = [ 0.1, 0.25, 0.5, 1.0, 2.0, 4.0 ];
+
+/// Number of passes kept for rewinding by default, 10 seconds at 60 fps
+pub const HISTORY: usize = 600;
+
+/// Handles the time control keys, and keeps a ring buffer of the sketch's recent states to rewind through
+///
+/// States are saved with Sketch::save once per pass, so when more than one update runs in a pass, rewinding goes back
+/// over all of them at once. Sketches which can't be saved can still be paused, stepped and scaled, but not rewound.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::canvas::RecordingCanvas;
+/// use rusty_nature_of_code::rng::SimRng;
+/// use rusty_nature_of_code::sketch::*;
+/// use rusty_nature_of_code::sketch::time::*;
+/// use rusty_nature_of_code::sketches::RandomWalkerOop;
+///
+/// let mut sketch = RandomWalkerOop::new();
+/// let mut ctx = Context::new( ( 640, 480 ), SimRng::new( 1 ) );
+/// let mut canvas = RecordingCanvas::new( 640.0, 480.0 );
+/// let mut time = TimeControls::new( HISTORY );
+/// sketch.setup( &mut ctx );
+///
+/// let mut states = Vec::new();
+/// for _ in 0..5 {
+///     time.remember( &sketch, &ctx );
+///     states.push( sketch.save().unwrap() );
+///     step( &mut sketch, &mut ctx, &mut canvas, false );
+/// }
+/// time.remember( &sketch, &ctx );
+///
+/// /* Rewinding twice goes back to the state after the third pass, and pauses */
+/// let rewind = Input { keys: vec![ REWIND_KEY, REWIND_KEY ], ..Input::default() };
+/// time.input( &rewind, &mut sketch, &mut ctx );
+/// assert!( ctx.paused );
+/// assert_eq!( ctx.frame, 3 );
+/// assert_eq!( sketch.save().unwrap(), states[3] );
+///
+/// /* Half speed updates every other pass */
+/// ctx.paused = false;
+/// ctx.time_scale = 0.5;
+/// step( &mut sketch, &mut ctx, &mut canvas, false );
+/// step( &mut sketch, &mut ctx, &mut canvas, false );
+/// assert_eq!( ctx.frame, 4 );
+/// ```
+///
+pub struct TimeControls {
+	/// Saved states, oldest first. The last is the current state unless the sketch has been updated since
+	history: VecDeque<Frame>,
+	/// Most states kept
+	capacity: usize,
+}
+
+impl TimeControls {
+	/// Creates controls keeping up to capacity passes to rewind through
+	pub fn new( capacity: usize ) -> TimeControls {
+		TimeControls { history: VecDeque::with_capacity( capacity ), capacity }
+	}
+
+	/// Number of passes which can be rewound
+	pub fn available( &self ) -> usize {
+		self.history.len().saturating_sub( 1 )
+	}
+
+	/// Handles the time control keys
+	pub fn input( &mut self, input: &Input, sketch: &mut dyn Sketch, ctx: &mut Context ) {
+		for key in input.keys.iter() {
+			match *key {
+				PAUSE_KEY => ctx.paused = !ctx.paused,
+				STEP_KEY => self.step( sketch, ctx ),
+				REWIND_KEY => self.rewind( sketch, ctx ),
+				SLOWER_KEY => ctx.time_scale = TIME_SCALES.iter().rev().copied().find( |scale| *scale < ctx.time_scale ).unwrap_or( TIME_SCALES[0] ),
+				FASTER_KEY => ctx.time_scale = TIME_SCALES.iter().copied().find( |scale| *scale > ctx.time_scale ).unwrap_or( TIME_SCALES[5] ),
+				_ => (),
+			}
+		}
+	}
+
+	/// Saves the sketch's state, unless it was already saved this pass. States newer than it, left by rewinding, are dropped
+	pub fn remember( &mut self, sketch: &dyn Sketch, ctx: &Context ) {
+		while self.history.back().is_some_and( |frame| frame.pass >= ctx.frame ) {
+			if self.history.back().is_some_and( |frame| frame.pass == ctx.frame ) {
+				return;
+			}
+			self.history.pop_back();
+		}
+		if let Some( state ) = sketch.save() {
+			if self.history.len() == self.capacity {
+				self.history.pop_front();
+			}
+			self.history.push_back( Frame { pass: ctx.frame, rng: ctx.rng.clone(), state } );
+		}
+	}
+
+	/// Pauses and runs a single update
+	pub fn step( &mut self, sketch: &mut dyn Sketch, ctx: &mut Context ) {
+		ctx.paused = true;
+		self.remember( sketch, ctx );
+		sketch.update( ctx );
+		ctx.frame += 1;
+		self.remember( sketch, ctx );
+	}
+
+	/// Pauses and loads the state saved before the current one, if there is one
+	pub fn rewind( &mut self, sketch: &mut dyn Sketch, ctx: &mut Context ) {
+		ctx.paused = true;
+		/* The last state is dropped only if it's the current one, otherwise the sketch goes back to it */
+		if self.history.back().is_some_and( |frame| frame.pass == ctx.frame ) && self.history.len() > 1 {
+			self.history.pop_back();
+		}
+		let Some( frame ) = self.history.back() else {
+			return;
+		};
+		match sketch.load( &frame.state ) {
+			Ok(()) => {
+				ctx.frame = frame.pass;
+				ctx.rng = frame.rng.clone();
+			}
+			Err( e ) => eprintln!( "failed to rewind: {}", e ),
+		}
+	}
+}
+
+impl Default for TimeControls {
+	fn default() -> Self {
+		TimeControls::new( HISTORY )
+	}
+}
@@ -0,0 +1,290 @@
+use std::io;
+use crate::bouncy_balls::prelude::*;
+#[cfg(feature = "parallel")]
+use crate::bouncy_balls::parallel::*;
+use crate::bouncy_balls::scene::Scene;
+use crate::boundary::*;
+use crate::canvas::Canvas;
+use crate::debug::*;
+use crate::events::*;
+use crate::register_sketch;
+use crate::schedule::{Schedule, Stage, System};
+use crate::sketch::{Context, Input, Key, Sketch};
+use crate::snapshot::{self, StoreSnapshot};
+use crate::world::*;
+use serde::{Deserialize, Serialize};
+
+/// Creates a world with the components and resources the bouncy balls systems use, shared by the sketches built on them
+///
+/// The resources are the scene the sketch was set up from, which also holds its spawn rules and gravity, the boundary, the
+/// obstacles and the grid of regions used to find collisions. The systems send Collision, WallHit, Wrapped, Spawned and
+/// Despawned events.
+pub fn balls() -> World {
+	let mut world = World::new();
+	world.register::<Atr>().register::<Pos>().register::<Vel>().register::<Acc>().register::<Mat>().register::<Shp>().on_despawn( leave_region );
+	world.insert_resource( Scene::default() );
+	world.insert_resource( Boundary::default() );
+	world.insert_resource( Vec::<Obstacle>::new() );
+	world.insert_resource( Regions::new() );
+	world.add_event::<Collision>().add_event::<WallHit>().add_event::<Wrapped>().add_event::<Spawned>().add_event::<Despawned>();
+	world
+}
+
+/// Draws the debug overlay for the balls
+pub fn debug( world: &World, canvas: &mut dyn Canvas ) {
+	let ( atr, pos, vel, acc ) = ( world.store::<Atr>(), world.store::<Pos>(), world.store::<Vel>(), world.store::<Acc>() );
+	grid_debug_system( canvas, &world.resource::<Regions>() );
+	collision_debug_system( canvas, world.events::<Collision>().as_slice(), &*pos, &*atr );
+	vector_debug_system( canvas, &*pos, &*vel, &*acc );
+	id_debug_system( canvas, &*pos );
+}
+
+/// The component stores encoded for a snapshot. The scene, boundary, obstacles and regions aren't saved, as setup builds them
+pub fn save( world: &World ) -> Vec<u8> {
+	snapshot::encode( &BallState {
+		atr: StoreSnapshot::save( &*world.store::<Atr>() ),
+		pos: StoreSnapshot::save( &*world.store::<Pos>() ),
+		vel: StoreSnapshot::save( &*world.store::<Vel>() ),
+		acc: StoreSnapshot::save( &*world.store::<Acc>() ),
+		mat: StoreSnapshot::save( &*world.store::<Mat>() ),
+		shp: StoreSnapshot::save( &*world.store::<Shp>() ),
+	})
+}
+
+/// Replaces the entities and stores with ones restored from state written by save, then rebuilds the regions
+pub fn load( world: &mut World, state: &[u8] ) -> io::Result<()> {
+	let state: BallState = snapshot::decode( state )?;
+	let mut ids = state.atr.ids();
+	ids.extend( state.pos.ids() );
+	let entities = world.restore_entities( &ids );
+
+	let ( mut atr, mut pos, mut vel, mut acc ) = ( world.store_mut::<Atr>(), world.store_mut::<Pos>(), world.store_mut::<Vel>(), world.store_mut::<Acc>() );
+	state.atr.restore( &entities, &mut *atr );
+	state.pos.restore( &entities, &mut *pos );
+	state.vel.restore( &entities, &mut *vel );
+	state.acc.restore( &entities, &mut *acc );
+	state.mat.restore( &entities, &mut *world.store_mut::<Mat>() );
+	state.shp.restore( &entities, &mut *world.store_mut::<Shp>() );
+	region_system( &*vel, &*atr, &mut world.resource_mut::<Regions>() );
+	Ok(())
+}
+
+/// The component stores of the balls, as saved in snapshots
+#[derive(Serialize, Deserialize)]
+struct BallState {
+	atr: StoreSnapshot<Attributes>,
+	pos: StoreSnapshot<[f32; 2]>,
+	vel: StoreSnapshot<[f32; 2]>,
+	acc: StoreSnapshot<[f32; 2]>,
+	mat: StoreSnapshot<Material>,
+	shp: StoreSnapshot<Shape>,
+}
+
+/// Adds the acceleration components to the velocity components
+pub fn acceleration() -> System<World> {
+	System::new( "acceleration", Stage::Integrate, |world: &mut World, _ctx| {
+		#[cfg(feature = "parallel")]
+		par_acceleration_system( &mut world.store_mut::<Acc>(), &mut world.store_mut::<Vel>() );
+		#[cfg(not(feature = "parallel"))]
+		acceleration_system( &mut *world.store_mut::<Acc>(), &mut *world.store_mut::<Vel>() );
+	})
+}
+
+/// Applies the velocity to the position vectors, then calculates what region each entity is in
+pub fn movement() -> System<World> {
+	System::new( "movement", Stage::Integrate, |world: &mut World, ctx| {
+		let config = &ctx.config.bouncy_balls;
+		#[cfg(feature = "parallel")]
+		par_movement_system( ctx.screen_size, config.columns, config.rows, &mut world.store_mut::<Vel>(), &mut world.store_mut::<Pos>(),
+							 &mut world.store_mut::<Atr>(), &mut world.resource_mut::<Regions>() );
+		#[cfg(not(feature = "parallel"))]
+		movement_system( ctx.screen_size, config.columns, config.rows, &mut *world.store_mut::<Vel>(), &mut *world.store_mut::<Pos>(),
+						 &mut *world.store_mut::<Atr>(), &mut world.resource_mut::<Regions>() );
+	})
+}
+
+/// Applies the Boundary resource's edges to the entities, combining their materials with solid edges, then sends what
+/// happened at the edges as events and despawns the entities which left through a despawn edge
+pub fn boundary() -> System<World> {
+	System::new( "boundary", Stage::Constrain, |world: &mut World, ctx| {
+		let boundary = *world.resource::<Boundary>();
+		#[cfg(feature = "parallel")]
+		let crossings = par_boundary_system( ctx.screen_size, &boundary,
+											 &mut world.store_mut::<Vel>(), &mut world.store_mut::<Pos>(), &mut world.store_mut::<Atr>(), &world.store::<Mat>(),
+											 &world.store::<Shp>() );
+		#[cfg(not(feature = "parallel"))]
+		let crossings = boundary_system( ctx.screen_size, &boundary,
+										 &mut *world.store_mut::<Vel>(), &mut *world.store_mut::<Pos>(), &mut *world.store_mut::<Atr>(), &*world.store::<Mat>(),
+										 &*world.store::<Shp>() );
+		send_crossings( world, crossings );
+	})
+}
+
+/// Despawns entities when they come to rest on a solid floor. Without one, nothing rests on the bottom edge
+pub fn drop_balls() -> System<World> {
+	System::new( "drop", Stage::Cleanup, |world: &mut World, _ctx| {
+		if !matches!( world.resource::<Boundary>().bottom, Edge::Bounce( _ ) ) {
+			return;
+		}
+		let to_drop = drop_system( &*world.store::<Vel>(), &*world.store::<Pos>(), &*world.store::<Atr>(), &*world.store::<Shp>() );
+		for despawned in to_drop {
+			/* An entity can be found more than once, but is only despawned once */
+			if world.contains( despawned.entity ) {
+				world.despawn( despawned.entity );
+				world.send( despawned );
+			}
+		}
+	})
+}
+
+/// Draws the entities at their positions as circles
+pub fn render() -> System<World> {
+	System::render( "render", |world: &World, canvas, _ctx| render_system( canvas, &*world.store::<Pos>(), &*world.store::<Atr>(), &*world.store::<Shp>() ) )
+}
+
+/// Key which saves the live balls as a scene file in the working directory
+pub const SAVE_SCENE_KEY: Key = Key::Char( 's' );
+
+/// Balls which fall under gravity, bounce off the edges of the screen and each other, and are replaced once they come to rest
+///
+/// The balls, gravity, boundary and obstacles come from the scene file in the config, or the default scene if there isn't one.
+pub struct BouncyBalls {
+	world: World,
+	schedule: Schedule<World>,
+}
+
+impl Default for BouncyBalls {
+	fn default() -> Self {
+		BouncyBalls::new()
+	}
+}
+
+impl BouncyBalls {
+	pub fn new() -> BouncyBalls {
+		let mut schedule = Schedule::new();
+		schedule
+			.at_start( clear_events )
+			/* Applies the scene's gravity to every entity with a acceleration component */
+			.add( System::new( "gravity", Stage::Forces, |world: &mut World, _ctx| {
+				let gravity = world.resource::<Scene>().gravity;
+				world.store_mut::<Acc>().par_for_each_mut( |_, a| a.y = gravity );
+			}))
+			.add( acceleration() )
+			/* Constrains all the velocities to the max speed in each direction, before they move anything */
+			.add( System::new( "max_speed", Stage::Integrate, |world: &mut World, _ctx| {
+				let max_speed = world.resource::<Scene>().max_speed;
+				world.store_mut::<Vel>().par_for_each_mut( |_, v| v.constrain( &(-max_speed..max_speed), &(-max_speed..max_speed) ) );
+			}).after( "acceleration" ).before( "movement" ) )
+			.add( movement() )
+			/* Checks for collisions in the regions movement just put the entities in, then bounces them apart. With the
+			   parallel feature, this and the other per entity systems run across threads, with the same results */
+			.add( System::new( "collision", Stage::Collide, |world: &mut World, _ctx| {
+				#[cfg(feature = "parallel")]
+				let collisions = par_collision_system( &mut world.store_mut::<Vel>(), &mut world.store_mut::<Pos>(), &mut world.store_mut::<Atr>(),
+													   &world.store::<Mat>(), &world.store::<Shp>(), &world.resource::<Regions>() );
+				#[cfg(not(feature = "parallel"))]
+				let collisions = collision_system( &mut *world.store_mut::<Vel>(), &mut *world.store_mut::<Pos>(), &mut *world.store_mut::<Atr>(),
+												   &*world.store::<Mat>(), &*world.store::<Shp>(), &mut world.resource_mut::<Regions>() );
+				world.resource_mut::<Events<Collision>>().extend( collisions );
+			}))
+			/* Applies the scene's boundary, bouncing entities off solid edges depending on their materials, then bounces them off
+			   the scene's obstacles */
+			.add( boundary() )
+			.add( System::new( "obstacle", Stage::Constrain, |world: &mut World, _ctx| {
+				obstacle_system( &world.resource::<Vec<Obstacle>>(), &mut *world.store_mut::<Vel>(), &mut *world.store_mut::<Pos>(), &mut *world.store_mut::<Atr>(),
+								 &*world.store::<Mat>(), &*world.store::<Shp>() );
+			}).after( "boundary" ) )
+			.add( drop_balls() )
+			/* Creates entities following the scene's spawn rules, replacing the ones just dropped */
+			.add( System::new( "spawn", Stage::Cleanup, |world: &mut World, ctx| spawn( world, ctx ) ).after( "drop" ) )
+			.add( System::render( "obstacle_render", |world: &World, canvas, _ctx| obstacle_render_system( canvas, &world.resource::<Vec<Obstacle>>() ) ) )
+			.add( orphan_check() )
+			.add( render() );
+		schedule.build().expect( "the bouncy balls systems are ordered consistently" );
+		BouncyBalls { world: balls(), schedule }
+	}
+}
+
+/// Creates entities following the scene's spawn rules
+fn spawn( world: &World, ctx: &mut Context ) {
+	world.resource::<Scene>().spawn( ctx.screen_size, &mut ctx.rng, world );
+}
+
+impl Sketch for BouncyBalls {
+	fn title( &self ) -> &str {
+		"bouncy balls"
+	}
+
+	fn background( &self ) -> Option<Rgba> {
+		Some( Rgba::WHITE )
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		let config = &ctx.config.bouncy_balls;
+		let world = &mut self.world;
+		world.insert_resource( regions( config.columns, config.rows ) );
+
+		/* Loads the scene, the config has already checked it loads so this only fails if the file changed since */
+		let scene = match &config.scene {
+			Some( path ) => Scene::load( path ).unwrap_or_else( |e| {
+				eprintln!( "couldn't load scene {}: {}, using the default scene", path.display(), e );
+				Scene::from_config( config )
+			}),
+			None => Scene::from_config( config ),
+		};
+		world.insert_resource( scene.obstacles() );
+		scene.populate( world );
+		world.insert_resource( scene.boundary );
+		world.insert_resource( scene );
+		/* Spawns the first balls, as the spawn system only runs at the end of each pass */
+		spawn( world, ctx );
+	}
+
+	fn update( &mut self, ctx: &mut Context ) {
+		self.schedule.update( &mut self.world, ctx );
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, ctx: &Context ) {
+		self.schedule.draw( &self.world, canvas, ctx );
+	}
+
+	fn debug( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+		debug( &self.world, canvas );
+	}
+
+	fn hud( &self, ctx: &Context ) -> Vec<String> {
+		match ctx.debug {
+			true => vec![
+				format!( "Collisions = {}", self.world.events::<Collision>().len() ),
+				format!( "Wall hits = {}", self.world.events::<WallHit>().len() ),
+				format!( "Wrapped = {}", self.world.events::<Wrapped>().len() ),
+				format!( "Spawned = {}", self.world.events::<Spawned>().len() ),
+				format!( "Despawned = {}", self.world.events::<Despawned>().len() ),
+			],
+			false => Vec::new(),
+		}
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		Some( save( &self.world ) )
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		load( &mut self.world, state )
+	}
+
+	fn input( &mut self, input: &Input, ctx: &mut Context ) {
+		if input.pressed( SAVE_SCENE_KEY ) {
+			let path = format!( "bouncy_balls_scene_{:05}.toml", ctx.frame );
+			let world = &self.world;
+			let scene = world.resource::<Scene>().capture( &*world.store::<Pos>(), &*world.store::<Vel>(), &*world.store::<Atr>(), &*world.store::<Mat>(),
+																	   &*world.store::<Shp>() );
+			match scene.save( &path ) {
+				Ok(()) => println!( "saved scene to {}", path ),
+				Err( e ) => eprintln!( "couldn't save scene to {}: {}", path, e ),
+			}
+		}
+	}
+}
+
+register_sketch!( "bouncy_balls", "Balls falling under gravity, bouncing off the edges and each other", BouncyBalls );
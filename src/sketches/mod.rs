@@ -0,0 +1,15 @@
+//! The Nature of Code examples as sketches, so they can be run in a window or drawn headless
+
+pub mod bouncy_balls;
+pub mod perlin_1d;
+pub mod random_blob;
+pub mod random_walker;
+pub mod random_walker_oop;
+pub mod smart_rockets;
+
+pub use bouncy_balls::BouncyBalls;
+pub use perlin_1d::Perlin1d;
+pub use random_blob::RandomBlob;
+pub use random_walker::RandomWalker;
+pub use random_walker_oop::RandomWalkerOop;
+pub use smart_rockets::SmartRockets;
@@ -0,0 +1,71 @@
+use std::io;
+use rand::RngCore;
+use noise::{NoiseFn, Perlin, Seedable};
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::register_sketch;
+use crate::sketch::{Context, Sketch};
+use crate::snapshot;
+
+/// A circle which moves smoothly back and forth across the screen, following 1d perlin noise
+pub struct Perlin1d {
+	/// Perlin noise, seeded from the sketch's random number generator in setup
+	perlin: Perlin,
+	/// Offset used to get values from the perlin noise, increased a little every pass
+	x_off: f64,
+	/// Latest value taken from the noise, from 0 to 1
+	val: f32,
+}
+
+impl Default for Perlin1d {
+	fn default() -> Self {
+		Perlin1d::new()
+	}
+}
+
+impl Perlin1d {
+	pub fn new() -> Perlin1d {
+		Perlin1d { perlin: Perlin::new( 0 ), x_off: 0.0, val: 0.0 }
+	}
+}
+
+impl Sketch for Perlin1d {
+	fn title( &self ) -> &str {
+		"perlin 1d"
+	}
+
+	fn size( &self ) -> ( i32, i32 ) {
+		( 480, 480 )
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		self.perlin = Perlin::new( ctx.rng.next_u32() );
+		self.x_off = ctx.rng.range( 0.0..100.0 );
+	}
+
+	fn update( &mut self, _ctx: &mut Context ) {
+		/* Get a value out of perlin, using the current x offset, with y offset set to 0 */
+		self.val = self.perlin.get( [self.x_off, 0.0] ).abs() as f32;
+		/* Increments offset by a small value to get get smooth randomness */
+		self.x_off += 0.01;
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+		/* Draws a circle with a x pos of the perlin value, and a y pos of 1/2 the screen height */
+		let ( width, height ) = canvas.size();
+		canvas.circle( Vec2::new( &(self.val * width), &(height / 2.0) ), 10.0, Rgba::WHITE );
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		Some( snapshot::encode( &( self.perlin.seed(), self.x_off, self.val ) ) )
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		let ( seed, x_off, val ) = snapshot::decode( state )?;
+		*self = Perlin1d { perlin: Perlin::new( seed ), x_off, val };
+		Ok(())
+	}
+}
+
+register_sketch!( "perlin_1d", "A circle moving back and forth following 1d perlin noise", Perlin1d );
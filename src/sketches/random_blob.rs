@@ -0,0 +1,100 @@
+use std::io;
+use rand::prelude::SliceRandom;
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::register_sketch;
+use crate::sketch::{Context, Sketch};
+use crate::snapshot::{self, Snap};
+
+/// A position and a velocity
+type Circle = ( Vec2, Vec2 );
+
+/// Each function applies the velocity to the position differently
+const MOVES: [fn( &mut Circle ); 8] = [
+	|( pos, vel )| {
+		pos.x *= vel.x;
+		pos.y *= vel.y;
+	},
+	|( pos, vel )| {
+		pos.x *= vel.x;
+		pos.y /= vel.y;
+	},
+	|( pos, vel )| {
+		pos.x /= vel.x;
+		pos.y *= vel.y;
+	},
+	|( pos, vel )| {
+		pos.x /= vel.x;
+		pos.y *= vel.y;
+	},
+	|( pos, vel )| {
+		pos.x *= vel.y.sqrt();
+		pos.y /= vel.x.powf( 2.0 );
+	},
+	|( pos, vel )| {
+		pos.x /= vel.y.sqrt();
+		pos.y *= vel.y.powf( 2.0 );
+	},
+	|( pos, vel )| {
+		pos.x -= vel.y.cos();
+		pos.y += vel.x.sin();
+	},
+	|( pos, vel )| {
+		pos.x += vel.x.cos();
+		pos.y -= vel.x.sin();
+	},
+];
+
+/// 500 circles starting in the center of the screen, each pass one randomly chosen move is applied to all of them
+#[derive(Default)]
+pub struct RandomBlob {
+	circles: Vec<Circle>,
+}
+
+impl RandomBlob {
+	pub fn new() -> RandomBlob {
+		RandomBlob::default()
+	}
+}
+
+impl Sketch for RandomBlob {
+	fn title( &self ) -> &str {
+		"random blob"
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		let ( width, height ) = ctx.size();
+		self.circles = vec![ ( Vec2::new( &(width / 2.0), &(height / 2.0) ), Vec2::default() ); 500 ];
+	}
+
+	fn update( &mut self, ctx: &mut Context ) {
+		/* Generate random velocities */
+		for ( _, vel ) in self.circles.iter_mut() {
+			*vel = ctx.rng.vec2( &(0.995..1.005), &(0.995..1.005) );
+		}
+		/* Chooses a random function from MOVES and applies it to each circle */
+		let chosen = MOVES.choose( &mut ctx.rng ).unwrap();
+		self.circles.iter_mut().for_each( chosen );
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+		let radius = canvas.size().0 / 50.0;
+		for ( pos, _ ) in self.circles.iter() {
+			canvas.circle( *pos, radius, Rgba::WHITE );
+		}
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		let circles: Vec<_> = self.circles.iter().map( |( pos, vel )| ( pos.to_repr(), vel.to_repr() ) ).collect();
+		Some( snapshot::encode( &circles ) )
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		let circles: Vec<( [f32; 2], [f32; 2] )> = snapshot::decode( state )?;
+		self.circles = circles.into_iter().map( |( pos, vel )| ( Vec2::from_repr( pos ), Vec2::from_repr( vel ) ) ).collect();
+		Ok(())
+	}
+}
+
+register_sketch!( "random_blob", "500 circles pushed around by randomly chosen moves", RandomBlob );
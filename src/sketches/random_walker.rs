@@ -0,0 +1,105 @@
+use std::io;
+use miscmath::prelude::*;
+use crate::bouncy_balls::prelude::*;
+use crate::boundary::Boundary;
+use crate::canvas::Canvas;
+use crate::register_sketch;
+use crate::sketch::{Context, Sketch};
+use crate::schedule::{Schedule, Stage, System};
+use crate::events::clear_events;
+use crate::store::Components;
+use crate::world::*;
+use super::bouncy_balls::{self as balls, boundary, drop_balls, movement, render};
+
+/// An entity which takes a random step up, down, left or right each pass, leaving a trail, built on the bouncy balls systems
+pub struct RandomWalker {
+	world: World,
+	schedule: Schedule<World>,
+}
+
+impl Default for RandomWalker {
+	fn default() -> Self {
+		RandomWalker::new()
+	}
+}
+
+impl RandomWalker {
+	pub fn new() -> RandomWalker {
+		let mut schedule = Schedule::new();
+		schedule
+			.at_start( clear_events )
+			.add( System::new( "walk", Stage::Forces, |world: &mut World, ctx| {
+				let ( width, height ) = ctx.size();
+
+				/* Creates the walker if it isn't active */
+				while world.is_empty() {
+
+					/* Adds the entity at the center of the screen, with a velocity of 0 */
+					world.spawn( BallBundle {
+						atr: Attributes { mass: 1.0, color: Rgba::WHITE, radius: 2.0, angle: 0.0, spin: 0.0, row: 0, column: 0 },
+						pos: Vec2::new( &(width/2.0), &(height/2.0) ),
+						vel: Vec2::default(),
+						acc: Vec2::default(),
+						mat: Material::default(),
+						shape: None,
+					});
+				}
+
+				/* Randomly picks a number between 0 and 3, and then moves the entity up, right, down, or left
+				   depending on the number picked */
+				let step = match ctx.rng.range( 0..4 ) {
+					0 => Vec2::new( &0.0, &2.0 ),
+					1 => Vec2::new( &2.0, &0.0 ),
+					2 => Vec2::new( &0.0, &-2.0 ),
+					_ => Vec2::new( &-2.0, &0.0 ),
+				};
+				world.store_mut::<Vel>().for_each_mut( |_, vel| *vel = step );
+			}))
+			/* The bouncy balls systems move the walker, and wrap it around the edges of the screen */
+			.add( movement() )
+			.add( boundary() )
+			.add( drop_balls() )
+			.add( orphan_check() )
+			.add( render() );
+		schedule.build().expect( "the random walker systems are ordered consistently" );
+		RandomWalker { world: balls::balls(), schedule }
+	}
+}
+
+impl Sketch for RandomWalker {
+	fn title( &self ) -> &str {
+		"random walker"
+	}
+
+	fn background( &self ) -> Option<Rgba> {
+		None
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		let config = &ctx.config.bouncy_balls;
+		self.world.insert_resource( regions( config.columns, config.rows ) );
+		self.world.insert_resource( Boundary::torus() );
+	}
+
+	fn update( &mut self, ctx: &mut Context ) {
+		self.schedule.update( &mut self.world, ctx );
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, ctx: &Context ) {
+		self.schedule.draw( &self.world, canvas, ctx );
+	}
+
+	fn debug( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+		balls::debug( &self.world, canvas );
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		Some( balls::save( &self.world ) )
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		balls::load( &mut self.world, state )
+	}
+}
+
+register_sketch!( "random_walker", "A random walk built on the ecs, leaving a trail", RandomWalker );
@@ -0,0 +1,99 @@
+use std::io;
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::register_sketch;
+use crate::sketch::{Context, Sketch};
+use crate::snapshot::{self, Snap};
+
+/// A walker which holds its own state, rather than being made of components
+pub struct Walker {
+	pub pos: Vec2,
+	pub color: Rgba,
+	pub radius: f32,
+}
+
+impl Default for Walker {
+	fn default() -> Self {
+		Walker::new()
+	}
+}
+
+impl Walker {
+
+	/// Creates a new instance of Walker initialized with a radius of 2, position of (0,0), and a color of white
+	pub fn new() -> Walker {
+		Walker {
+			pos: Vec2::default(),
+			color: Rgba::WHITE,
+			radius: 2.0,
+		}
+	}
+
+	/// Adds a Vec2 to the position of the entity
+	pub fn update( &mut self, vel: &Vec2 ) {
+		self.pos.add( vel );
+	}
+
+	/// Draws Walker instance to the screen at the instance's position, with it's color and radius
+	pub fn draw<C: Canvas + ?Sized>( &self, canvas: &mut C ) {
+
+		/* Draws a circle at the entities position, flipped so that the coordinate (0,0) is the bottom left instead of the top left,
+		   with the entities radius and color */
+		canvas.circle( canvas.flip( &self.pos ), self.radius, self.color );
+	}
+}
+
+/// The random walker written with a plain struct instead of the ecs
+#[derive(Default)]
+pub struct RandomWalkerOop {
+	walker: Walker,
+}
+
+impl RandomWalkerOop {
+	pub fn new() -> RandomWalkerOop {
+		RandomWalkerOop::default()
+	}
+}
+
+impl Sketch for RandomWalkerOop {
+	fn title( &self ) -> &str {
+		"random walker oop"
+	}
+
+	fn background( &self ) -> Option<Rgba> {
+		None
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		/* Positions the walker at the center of the screen */
+		let ( width, height ) = ctx.size();
+		self.walker.pos = Vec2::new( &(width/2.0), &(height/2.0) );
+	}
+
+	fn update( &mut self, ctx: &mut Context ) {
+		/* Randomly picks a number between 0 and 3, and then moves the walker up, right, down, or left
+		   depending on the number picked */
+		match ctx.rng.range( 0..4 ) {
+			0 => self.walker.update( &Vec2::new( &0.0, &2.0 ) ),
+			1 => self.walker.update( &Vec2::new( &2.0, &0.0 ) ),
+			2 => self.walker.update( &Vec2::new( &0.0, &-2.0 ) ),
+			_ => self.walker.update( &Vec2::new( &-2.0, &0.0 ) ),
+		}
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+		self.walker.draw( canvas );
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		Some( snapshot::encode( &self.walker.pos.to_repr() ) )
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		self.walker.pos = Vec2::from_repr( snapshot::decode( state )? );
+		Ok(())
+	}
+}
+
+register_sketch!( "random_walker_oop", "A random walk written with a plain struct", RandomWalkerOop );
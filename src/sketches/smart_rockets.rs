@@ -0,0 +1,191 @@
+use std::io;
+use miscmath::prelude::*;
+use crate::canvas::Canvas;
+use crate::debug::{id_debug_system, vector_debug_system};
+use crate::config::SmartRocketsConfig;
+use crate::smart_rockets::data;
+use crate::smart_rockets::prelude::*;
+use crate::register_sketch;
+use crate::schedule::{Condition, Schedule, Stage, System};
+use crate::sketch::{Context, Sketch};
+use crate::snapshot::{self, StoreSnapshot};
+use crate::store::Components;
+use crate::boundary::{send_crossings, Boundary};
+use crate::events::{clear_events, Despawned, Events, Spawned, WallHit, Wrapped};
+use crate::world::{orphan_check, Acc, Mass, Pos, Vel, World};
+use serde::{Deserialize, Serialize};
+
+/// Creates a world with the components the smart rockets systems use, and the rockets' data::World as a resource
+pub fn rockets() -> World {
+	let mut world = World::new();
+	world.register::<Atr>().register::<Pos>().register::<Vel>().register::<Acc>().register::<Mass>().register::<Gene>();
+	world.add_event::<WallHit>().add_event::<Wrapped>().add_event::<Spawned>().add_event::<Despawned>().add_event::<TargetReached>();
+	world.insert_resource( arena( 640.0, 480.0, &SmartRocketsConfig::default() ) );
+	world
+}
+
+/// Rockets which learn to fly to a target, each generation bred from the ones which got closest
+pub struct SmartRockets {
+	world: World,
+	schedule: Schedule<World>,
+}
+
+/// The world and component stores, as saved in snapshots
+#[derive(Serialize, Deserialize)]
+struct State {
+	world: data::World,
+	atr: StoreSnapshot<Attributes>,
+	pos: StoreSnapshot<[f32; 2]>,
+	vel: StoreSnapshot<[f32; 2]>,
+	acc: StoreSnapshot<[f32; 2]>,
+	mass: StoreSnapshot<f32>,
+	gene: StoreSnapshot<Vec<usize>>,
+}
+
+/// Creates the rockets' data::World for a screen of width by height, with the rockets starting at the bottom and the target
+/// at the top
+fn arena( width: f32, height: f32, config: &SmartRocketsConfig ) -> data::World {
+	data::World {
+		width,
+		height,
+		default_pos: Vec2::new( &(width/2.0), &0.0 ),
+		default_vel: Vec2::new( &0.0, &0.0 ),
+		target_pos: Vec2::new( &(width/2.0), &(height) ),
+		mutation_chance: config.mutation_chance,
+		lifespan: config.lifespan,
+		boundary: Boundary::default(),
+		gen_done: false
+	}
+}
+
+impl Default for SmartRockets {
+	fn default() -> Self {
+		SmartRockets::new()
+	}
+}
+
+impl SmartRockets {
+	pub fn new() -> SmartRockets {
+		let mut schedule = Schedule::new();
+		schedule
+			.at_start( clear_events )
+			/* Breeds a new population once the last generation is done */
+			.add( System::new( "genetic", Stage::Forces, |world: &mut World, ctx| {
+				let mut arena = world.resource_mut::<data::World>();
+				if arena.gen_done {
+					if let Err( e ) = genetic_system( &arena, &mut ctx.rng, &mut *world.store_mut::<Pos>(), &mut *world.store_mut::<Vel>(),
+													 &mut *world.store_mut::<Atr>(), &mut *world.store_mut::<Gene>() ) {
+						eprintln!( "couldn't breed a new generation: {}", e );
+					}
+					arena.gen_done = false;
+				}
+			}))
+			/* Sets each entities acceleration to the thrust its dna picks for this pass */
+			.add( System::new( "thrust", Stage::Forces, |world: &mut World, ctx| {
+				thrust_system( &world.resource::<data::World>(), ctx.frame, &*world.store::<Gene>(), &mut *world.store_mut::<Acc>() );
+			}).after( "genetic" ) )
+			/* Moves entities based on their acceleration and velocity */
+			.add( System::new( "movement", Stage::Integrate, |world: &mut World, _ctx| {
+				movement_system( &mut *world.store_mut::<Pos>(), &mut *world.store_mut::<Vel>(), &mut *world.store_mut::<Acc>(), &*world.store::<Mass>() );
+			}))
+			/* Applies the arena's boundary to entities which have reached the edges of the screen, bouncing them off its
+			   solid edges */
+			.add( System::new( "boundary", Stage::Constrain, |world: &mut World, _ctx| {
+				let crossings = boundary_system( &world.resource::<data::World>(), &mut *world.store_mut::<Vel>(), &mut *world.store_mut::<Pos>(), &*world.store::<Atr>() );
+				send_crossings( world, crossings );
+			}))
+			/* Finds the rockets which have just reached the target */
+			.add( System::new( "target", Stage::Collide, |world: &mut World, ctx| {
+				let reached = target_system( &world.resource::<data::World>(), ctx.frame, &*world.store::<Pos>(), &mut *world.store_mut::<Atr>() );
+				world.resource_mut::<Events<TargetReached>>().extend( reached );
+			}))
+			/* Ends the generation on the last pass of its lifespan */
+			.add( System::new( "generation", Stage::Cleanup, |world: &mut World, _ctx| world.resource_mut::<data::World>().gen_done = true )
+				.run_if( Condition::If( |ctx| ctx.frame.is_multiple_of( ctx.config.smart_rockets.lifespan ) ) ) )
+			.add( orphan_check() )
+			/* Draws the target, and the entities at their positions as circles */
+			.add( System::render( "render", |world: &World, canvas, _ctx| {
+				render_system( canvas, &world.resource::<data::World>(), &*world.store::<Pos>(), &*world.store::<Atr>() );
+			}));
+		schedule.build().expect( "the smart rockets systems are ordered consistently" );
+		SmartRockets { world: rockets(), schedule }
+	}
+}
+
+impl Sketch for SmartRockets {
+	fn title( &self ) -> &str {
+		"smart rockets"
+	}
+
+	fn setup( &mut self, ctx: &mut Context ) {
+		let ( width, height ) = ctx.size();
+		let config = &ctx.config.smart_rockets;
+		let world = &mut self.world;
+		let arena = arena( width, height, config );
+
+		let rng = &mut ctx.rng;
+		while world.len() < config.rockets {
+			world.spawn( RocketBundle {
+				atr: Attributes { color: Rgba::new( rng.range(100..255), rng.range(100..255), rng.range(100..255), 100 ), radius: 10.0, reached: false },
+				pos: arena.default_pos,
+				vel: arena.default_vel,
+				acc: Vec2::default(),
+				mass: 1.1,
+				gene: (0..arena.lifespan).map( |_| rng.range( 0..THRUSTS ) ).collect(),
+			});
+		}
+		world.insert_resource( arena );
+	}
+
+	fn update( &mut self, ctx: &mut Context ) {
+		self.schedule.update( &mut self.world, ctx );
+	}
+
+	fn draw( &self, canvas: &mut dyn Canvas, ctx: &Context ) {
+		self.schedule.draw( &self.world, canvas, ctx );
+	}
+
+	fn save( &self ) -> Option<Vec<u8>> {
+		let world = &self.world;
+		Some( snapshot::encode( &State {
+			world: world.resource::<data::World>().clone(),
+			atr: StoreSnapshot::save( &*world.store::<Atr>() ),
+			pos: StoreSnapshot::save( &*world.store::<Pos>() ),
+			vel: StoreSnapshot::save( &*world.store::<Vel>() ),
+			acc: StoreSnapshot::save( &*world.store::<Acc>() ),
+			mass: StoreSnapshot::save( &*world.store::<Mass>() ),
+			gene: StoreSnapshot::save( &*world.store::<Gene>() ),
+		}))
+	}
+
+	fn load( &mut self, state: &[u8] ) -> io::Result<()> {
+		let state: State = snapshot::decode( state )?;
+		let world = &mut self.world;
+		let entities = world.restore_entities( &state.gene.ids() );
+		world.insert_resource( state.world );
+		state.atr.restore( &entities, &mut *world.store_mut::<Atr>() );
+		state.pos.restore( &entities, &mut *world.store_mut::<Pos>() );
+		state.vel.restore( &entities, &mut *world.store_mut::<Vel>() );
+		state.acc.restore( &entities, &mut *world.store_mut::<Acc>() );
+		state.mass.restore( &entities, &mut *world.store_mut::<Mass>() );
+		state.gene.restore( &entities, &mut *world.store_mut::<Gene>() );
+		Ok(())
+	}
+
+	fn debug( &self, canvas: &mut dyn Canvas, _ctx: &Context ) {
+		let world = &self.world;
+		vector_debug_system( canvas, &*world.store::<Pos>(), &*world.store::<Vel>(), &*world.store::<Acc>() );
+		id_debug_system( canvas, &*world.store::<Pos>() );
+	}
+
+	fn hud( &self, ctx: &Context ) -> Vec<String> {
+		let mut reached = 0;
+		self.world.store::<Atr>().for_each( |_, atr| if atr.reached { reached += 1 } );
+		vec![
+			format!( "Generation = {}", ctx.frame / self.world.resource::<data::World>().lifespan ),
+			format!( "Reached target = {}", reached ),
+		]
+	}
+}
+
+register_sketch!( "smart_rockets", "Rockets evolving to reach a target with a genetic algorithm", SmartRockets );
@@ -1,17 +1,80 @@
+use misc_ecs::prelude::Entity;
 use miscmath::prelude::Vec2;
-use raylib::prelude::Color;
+use serde::{Deserialize, Serialize};
+use crate::color::Rgba;
+use crate::component;
+use crate::boundary::Boundary;
+use crate::store::Components;
+use crate::world::{Acc, Bundle, Mass, Pos, Vel};
 
+component!(
+	/// Attributes of a rocket
+	Atr => Attributes,
+	/// The dna of a rocket, an index into the thrusts for each pass of its life
+	Gene => Vec<usize>,
+);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct World {
 	pub width: f32,
 	pub height: f32,
+	#[serde(with = "crate::snapshot::vec2")]
 	pub default_pos: Vec2,
+	#[serde(with = "crate::snapshot::vec2")]
 	pub default_vel: Vec2,
+	#[serde(with = "crate::snapshot::vec2")]
 	pub target_pos: Vec2,
 	pub mutation_chance: f64,
+	/// Number of passes a generation lives for, which is also the length of each rocket's dna
+	pub lifespan: usize,
+	/// What happens to rockets at each edge of the arena
+	#[serde(default)]
+	pub boundary: Boundary,
 	pub gen_done: bool
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Attributes {
 	pub radius: f32,
-	pub color: Color
-}
\ No newline at end of file
+	pub color: Rgba,
+	/// Whether the rocket has touched the target this generation
+	#[serde(default)]
+	pub reached: bool,
+}
+
+/// A rocket touched the target, sent once per generation when it first does
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TargetReached {
+	pub entity: Entity,
+	/// Pass it touched the target on
+	pub pass: usize,
+}
+
+/// The components of a rocket
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RocketBundle {
+	pub atr: Attributes,
+	pub pos: Vec2,
+	pub vel: Vec2,
+	pub acc: Vec2,
+	pub mass: f32,
+	pub gene: Vec<usize>,
+}
+
+impl Bundle for RocketBundle {
+	fn insert( self, world: &crate::world::World, entity: Entity ) {
+		world.store_mut::<Atr>().add( entity, self.atr );
+		world.store_mut::<Pos>().add( entity, self.pos );
+		world.store_mut::<Vel>().add( entity, self.vel );
+		world.store_mut::<Acc>().add( entity, self.acc );
+		world.store_mut::<Mass>().add( entity, self.mass );
+		world.store_mut::<Gene>().add( entity, self.gene );
+	}
+}
@@ -0,0 +1,304 @@
+//! Runs smart rocket evolution without a window, so many seeds and parameter combinations can be evolved in parallel
+//! and summarised to csv files.
+
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use misc_ecs::prelude::*;
+use miscmath::prelude::*;
+use crate::boundary::Boundary;
+use crate::rng::SimRng;
+use crate::smart_rockets::prelude::*;
+
+/// Parameters for a single headless evolution run
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunConfig {
+	/// Width of the world
+	pub width: f32,
+	/// Height of the world
+	pub height: f32,
+	/// Number of rockets in each generation
+	pub population: usize,
+	/// Number of passes each generation lives for
+	pub lifespan: usize,
+	/// Chance of a gene mutating when a new generation is bred
+	pub mutation_chance: f64,
+	/// Number of generations to evolve
+	pub generations: usize,
+	/// Seed of the run's SimRng, the same config always evolves the same way
+	pub seed: u64,
+}
+
+impl Default for RunConfig {
+	fn default() -> Self {
+		RunConfig {
+			width: 640.0,
+			height: 480.0,
+			population: 10,
+			lifespan: 10,
+			mutation_chance: 0.1,
+			generations: 100,
+			seed: 0,
+		}
+	}
+}
+
+impl RunConfig {
+	/// Checks the parameters can be run, returning an InvalidInput error describing the first bad value
+	pub fn validate(&self) -> io::Result<()> {
+		let invalid = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+
+		if self.population < 2 {
+			return invalid(format!("population must be at least 2, got {}", self.population));
+		}
+		if self.lifespan == 0 {
+			return invalid("lifespan must be at least 1".to_string());
+		}
+		if !(0.0..=1.0).contains(&self.mutation_chance) {
+			return invalid(format!("mutation chance must be between 0 and 1, got {}", self.mutation_chance));
+		}
+		if self.width <= 0.0 || self.height <= 0.0 {
+			return invalid(format!("world size must be positive, got {}x{}", self.width, self.height));
+		}
+		Ok(())
+	}
+
+	/// File name used for this configuration's summary csv
+	pub fn file_name(&self) -> String {
+		format!("mutation_{}_population_{}_lifespan_{}.csv", self.mutation_chance, self.population, self.lifespan)
+	}
+}
+
+/// How close a generation got to the target, measured at the end of its lifespan
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GenerationStats {
+	/// Index of the generation, starting at 0
+	pub generation: usize,
+	/// Distance to the target of the closest rocket
+	pub best_distance: f32,
+	/// Mean distance to the target over the whole population
+	pub mean_distance: f32,
+}
+
+/// Evolves a population for config.generations generations, returning the stats of each generation, or an error if the
+/// config isn't valid
+///
+/// # Examples
+///
+/// ```
+/// use rusty_nature_of_code::smart_rockets::headless::*;
+///
+/// let config = RunConfig { generations: 3, ..Default::default() };
+/// let stats = run(&config).unwrap();
+///
+/// assert_eq!(stats.len(), 3);
+/// ```
+///
+pub fn run(config: &RunConfig) -> io::Result<Vec<GenerationStats>> {
+	config.validate()?;
+	let ( width, height ) = ( config.width, config.height );
+
+	let mut rng = SimRng::new(config.seed);
+	let mut entity_manager = EntityManager::new();
+	let mut pos_store = HashStore::new();
+	let mut vel_store = HashStore::new();
+	let mut acc_store = HashStore::new();
+	let mut atr_store = HashStore::new();
+	let mut mass_store: HashStore<f32> = HashStore::new();
+	let mut gene_store: HashStore<Vec<usize>> = HashStore::new();
+
+	let world = World {
+		width,
+		height,
+		default_pos: Vec2::new( &(width/2.0), &0.0 ),
+		default_vel: Vec2::new(&0.0, &0.0),
+		target_pos: Vec2::new(&(width/2.0), &(height)),
+		mutation_chance: config.mutation_chance,
+		lifespan: config.lifespan,
+		boundary: Boundary::default(),
+		gen_done: false
+	};
+
+	while entity_manager.len() < config.population {
+		let entity = entity_manager.next();
+		atr_store.add(entity, Attributes { color: Rgba::WHITE, radius: 10.0, reached: false });
+		pos_store.add(entity, world.default_pos);
+		vel_store.add(entity, world.default_vel);
+		acc_store.add(entity, Vec2::default());
+		mass_store.add(entity, 1.1);
+		gene_store.add(entity, (0..world.lifespan).map(|_| rng.range(0..THRUSTS)).collect());
+	}
+
+	let mut stats = Vec::with_capacity(config.generations);
+	let mut pass = 0;
+
+	for generation in 0..config.generations {
+		/* Lets the current generation fly for its lifespan */
+		for _ in 0..world.lifespan {
+			thrust_system(&world, pass, &gene_store, &mut acc_store);
+			movement_system(&mut pos_store, &mut vel_store, &mut acc_store, &mass_store);
+			boundary_system(&world, &mut vel_store, &mut pos_store, &atr_store);
+			pass += 1;
+		}
+
+		/* Scores the generation before the genetic system resets the rockets. The distances are sorted before summing
+		   so the mean doesn't depend on the order the store iterates in */
+		let mut distances = Vec::with_capacity(pos_store.len());
+		pos_store.for_each(|_, pos| distances.push(distance_to_target(&world, pos)));
+		distances.sort_by(f32::total_cmp);
+		stats.push(GenerationStats {
+			generation,
+			best_distance: distances.first().copied().unwrap_or(f32::MAX),
+			mean_distance: distances.iter().sum::<f32>() / distances.len() as f32,
+		});
+
+		genetic_system(&world, &mut rng, &mut pos_store, &mut vel_store, &mut atr_store, &mut gene_store)
+			.map_err(|e| io::Error::other(format!("couldn't breed generation {}: {}", generation + 1, e)))?;
+	}
+	Ok(stats)
+}
+
+/// The seed of a run and the stats of each of its generations
+pub type SeedStats = (u64, Vec<GenerationStats>);
+
+/// Writes the stats of several seeds of one configuration as csv, one row per seed and generation
+pub fn write_csv<W: Write>(writer: &mut W, runs: &[SeedStats]) -> io::Result<()> {
+	writeln!(writer, "seed,generation,best_distance,mean_distance")?;
+	for (seed, stats) in runs {
+		for generation in stats {
+			writeln!(writer, "{},{},{},{}", seed, generation.generation, generation.best_distance, generation.mean_distance)?;
+		}
+	}
+	Ok(())
+}
+
+/// A hyperparameter sweep, running every combination of the listed values once per seed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep {
+	/// Mutation chances to try
+	pub mutation_chances: Vec<f64>,
+	/// Population sizes to try
+	pub populations: Vec<usize>,
+	/// Lifespans to try
+	pub lifespans: Vec<usize>,
+	/// Seeds each combination is run with
+	pub seeds: Vec<u64>,
+	/// Number of generations each run evolves for
+	pub generations: usize,
+	/// Width of the world
+	pub width: f32,
+	/// Height of the world
+	pub height: f32,
+}
+
+impl Default for Sweep {
+	fn default() -> Self {
+		let run = RunConfig::default();
+		Sweep {
+			mutation_chances: vec![run.mutation_chance],
+			populations: vec![run.population],
+			lifespans: vec![run.lifespan],
+			seeds: vec![run.seed],
+			generations: run.generations,
+			width: run.width,
+			height: run.height,
+		}
+	}
+}
+
+impl Sweep {
+	/// Every combination of parameters in the sweep, with the seed left at its default
+	pub fn configs(&self) -> Vec<RunConfig> {
+		let mut configs = Vec::new();
+		for &mutation_chance in &self.mutation_chances {
+			for &population in &self.populations {
+				for &lifespan in &self.lifespans {
+					configs.push(RunConfig {
+						width: self.width,
+						height: self.height,
+						population,
+						lifespan,
+						mutation_chance,
+						generations: self.generations,
+						seed: 0,
+					});
+				}
+			}
+		}
+		configs
+	}
+
+	/// Checks every configuration can be run and no value is listed twice, returning an InvalidInput error describing the
+	/// first problem. Each configuration's csv is named after its values, so a repeated value would overwrite another's file
+	pub fn validate(&self) -> io::Result<()> {
+		once("mutation chance", &self.mutation_chances)?;
+		once("population", &self.populations)?;
+		once("lifespan", &self.lifespans)?;
+		once("seed", &self.seeds)?;
+		for config in self.configs() {
+			config.validate()?;
+		}
+		Ok(())
+	}
+
+	/// Runs the sweep on up to threads threads, writing a summary csv per configuration into out_dir and
+	/// returning the paths written, or an error if the sweep isn't valid
+	pub fn run(&self, out_dir: &Path, threads: usize) -> io::Result<Vec<PathBuf>> {
+		self.validate()?;
+		let configs = self.configs();
+		fs::create_dir_all(out_dir)?;
+
+		/* Every (configuration, seed) pair is a job, threads pull the next job index until none are left */
+		let jobs: Vec<(usize, usize)> = (0..configs.len())
+			.flat_map(|config| (0..self.seeds.len()).map(move |seed| (config, seed)))
+			.collect();
+		let next_job = AtomicUsize::new(0);
+		let results: Mutex<Vec<Vec<SeedStats>>> = Mutex::new(
+			configs.iter().map(|_| self.seeds.iter().map(|seed| (*seed, Vec::new())).collect()).collect()
+		);
+		let failed: Mutex<Option<io::Error>> = Mutex::new(None);
+
+		/* A failed run stops its thread, and the first failure is returned once every thread has finished */
+		thread::scope(|scope| {
+			for _ in 0..threads.max(1).min(jobs.len().max(1)) {
+				scope.spawn(|| {
+					while let Some(&(config, seed)) = jobs.get(next_job.fetch_add(1, Ordering::Relaxed)) {
+						match self::run(&RunConfig { seed: self.seeds[seed], ..configs[config].clone() }) {
+							Ok(stats) => results.lock().unwrap()[config][seed].1 = stats,
+							Err(e) => {
+								failed.lock().unwrap().get_or_insert(e);
+								return;
+							}
+						}
+					}
+				});
+			}
+		});
+		if let Some(e) = failed.into_inner().unwrap() {
+			return Err(e);
+		}
+
+		let results = results.into_inner().unwrap();
+		let mut paths = Vec::with_capacity(configs.len());
+		for (config, runs) in configs.iter().zip(results.iter()) {
+			let path = out_dir.join(config.file_name());
+			let mut writer = BufWriter::new(File::create(&path)?);
+			write_csv(&mut writer, runs)?;
+			writer.flush()?;
+			paths.push(path);
+		}
+		Ok(paths)
+	}
+}
+
+/// An InvalidInput error naming the first value listed more than once, if there is one
+fn once<T: PartialEq + Display>(name: &str, values: &[T]) -> io::Result<()> {
+	match values.iter().enumerate().find(|(i, value)| values[..*i].contains(value)) {
+		Some((_, value)) => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} {} is listed more than once", name, value))),
+		None => Ok(()),
+	}
+}
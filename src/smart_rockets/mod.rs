@@ -1,10 +1,11 @@
 use misc_ecs::prelude::Entity;
 use rand::distributions::WeightedError;
-use rand::prelude::{SliceRandom, ThreadRng};
-use rand::thread_rng;
+use rand::prelude::SliceRandom;
+use crate::rng::SimRng;
 
 pub mod data;
 pub mod systems;
+pub mod headless;
 pub mod prelude;
 
 /// Chooses two entities based on their weights
@@ -15,24 +16,21 @@ pub mod prelude;
 ///
 /// ```
 ///
-pub fn choose_two(weights: &[(Entity, u32);10]) -> Result<(&Entity, &Entity), WeightedError> {
-	let mut rng = thread_rng();
-	let first_chosen = choose_one(weights, &mut rng)?;
-	let mut second_chosen = choose_one(weights, &mut rng)?;
-	while first_chosen == second_chosen {
-		second_chosen = choose_one(weights, &mut rng)?;
-	}
+pub fn choose_two<'a>(weights: &'a [(Entity, u32)], rng: &mut SimRng) -> Result<(&'a Entity, &'a Entity), WeightedError> {
+	let first_chosen = choose_one(weights, rng)?;
+
+	/* Chooses the second entity from the remaining ones, so the loop can't spin forever when only the first has any weight.
+	   If no other entity has any weight, the first is paired with itself */
+	let remaining: Vec<(Entity, u32)> = weights.iter()
+		.map(|(entity, weight)| if entity == first_chosen { (*entity, 0) } else { (*entity, *weight) })
+		.collect();
+	let second_chosen = match choose_one(&remaining, rng) {
+		Ok(second) => weights.iter().find(|(entity, _)| entity == second).map(|(entity, _)| entity).unwrap(),
+		Err(_) => first_chosen
+	};
 	Ok((first_chosen, second_chosen))
 }
 
-fn choose_one<'a>(weights: &'a [(Entity, u32);10], rng: &mut ThreadRng) -> Result<&'a Entity, WeightedError> {
-	
-	return match weights.choose_weighted(rng, |entity| { entity.1 }) {
-		Ok((entity, _)) => {
-			Ok(entity)
-		}
-		Err(e) => {
-			Err(e)
-		}
-	};
+fn choose_one<'a>(weights: &'a [(Entity, u32)], rng: &mut SimRng) -> Result<&'a Entity, WeightedError> {
+	weights.choose_weighted(rng, |entity| { entity.1 }).map(|(entity, _)| entity)
 }
\ No newline at end of file
@@ -1,2 +1,3 @@
 pub use crate::smart_rockets::systems::*;
-pub use crate::smart_rockets::data::*;
\ No newline at end of file
+pub use crate::smart_rockets::data::*;
+pub use crate::color::Rgba;
\ No newline at end of file
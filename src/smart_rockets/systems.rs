@@ -1,33 +1,47 @@
-use misc_ecs::prelude::{Entity, Store};
-use miscmath::{map, random};
-use miscmath::prelude::Vec2;
-use rand::{Rng, thread_rng};
-use raylib::prelude::{Color, RaylibDraw, RaylibDrawHandle};
+use misc_ecs::prelude::Entity;
+use rand::distributions::WeightedError;
+use miscmath::map;
+use miscmath::prelude::{UnitF, Vec2};
+use crate::canvas::Canvas;
+use crate::color::Rgba;
+use crate::boundary::Crossing;
+use crate::material::Material;
+use crate::query::*;
+use crate::rng::SimRng;
 use crate::smart_rockets::choose_two;
-use crate::smart_rockets::prelude::{Attributes, World};
+use crate::smart_rockets::prelude::{Attributes, TargetReached, World};
+use crate::store::Components;
 
-pub fn render_system<P: Store<Vec2>, A: Store<Attributes>>(display: &mut RaylibDrawHandle,
-														   world: &World,
-														   pos_store: &P,
-														   atr_store: &A ) {
+/// Radius of the target rockets are aiming for
+pub const TARGET_RADIUS: f32 = 25.0;
+
+/// Draws the rockets as circles, and the target they are aiming for
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn render_system<C: Canvas + ?Sized, P: Components<Vec2>, A: Components<Attributes>>(canvas: &mut C,
+																	   world: &World,
+																	   pos_store: &P,
+																	   atr_store: &A ) {
 	/* Applies a closure for each entity with a position */
 	pos_store.for_each( | entity, pos| {
 		/* If the current entity has a attributes component, assign it to atr and run the following code block */
 		if let Some( atr ) = atr_store.get( entity ) {
 			
-			/* Maps the y value, from the range 0->screen_height, to the range screen_height->0, so that the coordinate (0,0) is the bottom left
-			   instead of the top left */
-			let y = map( pos.y, 0.0..world.height, world.height..0.0 );
-			/* Draws a circle at the entities position, with the entities radius and color */
-			display.draw_circle(pos.x as i32, y as i32, atr.radius, atr.color );
+			/* Draws a circle at the entities position, flipped so that the coordinate (0,0) is the bottom left instead of the top left,
+			   with the entities radius and color */
+			canvas.circle( canvas.flip( pos ), atr.radius, atr.color );
 		}
 	});
 	
-	let y = map( world.target_pos.y, 0.0..world.height, world.height..0.0 );
-	display.draw_circle(world.target_pos.x as i32, y as i32, 25.0, Color::RED);
+	canvas.circle( canvas.flip( &world.target_pos ), TARGET_RADIUS, Rgba::RED );
 }
 
-/// Bounds entities position to be within the screen, and inverts their velocities when they hit the edges
+/// Applies the arena's boundary to the entities, bouncing them off its solid edges, returning what happened at the edges
 ///
 /// # Examples
 ///
@@ -35,33 +49,53 @@ pub fn render_system<P: Store<Vec2>, A: Store<Attributes>>(display: &mut RaylibD
 ///
 /// ```
 ///
-pub fn boundary_system<V: Store<Vec2>, P: Store<Vec2>, A: Store<Attributes>>(world: &World,
+pub fn boundary_system<V: Components<Vec2>, P: Components<Vec2>, A: Components<Attributes>>(world: &World,
 																			 vel_store: &mut V,
 																			 pos_store: &mut P,
-																			 atr_store: &A ) {
-	/* Applies a closure to each entity with a pos component */
-	pos_store.for_each_mut( | entity, pos | {
-		
-		/* Gets current entities attributes component */
-		let atr = atr_store.get( entity ).unwrap();
+																			 atr_store: &A ) -> Vec<Crossing> {
+	let mut crossings = Vec::new();
+
+	/* Applies a closure to each entity with a position, velocity and attributes */
+	query( ( write( pos_store ), write( vel_store ), read( atr_store ) ) ).for_each( | entity, ( pos, vel, atr ) | {
 		
-		/* If current entity has a velocity component, assign it to vel then run the following code block */
-		if let Some( vel ) = vel_store.get_mut( entity ) {
-			
-			/* Constrain entities position to within the screen, factoring in the size of the entity */
-			pos.constrain( &((atr.radius)..(world.width - atr.radius)), &((atr.radius)..(world.height - atr.radius)) );
-			
-			/* If entity hits the edges, invert their velocity and reduce it based on mass, then reduce it based on surface friction */
-			if pos.x - atr.radius < 0.00000001 { vel.x *= -1.0; vel.y *= 0.99; }
-			if pos.y - atr.radius < 0.00000001 { vel.y *= -1.0; vel.x *= 0.9; }
-			if pos.x > world.width - atr.radius - 0.0001 { vel.x *= -1.0; vel.y *= 0.99; }
-			if pos.y > world.height - atr.radius - 0.0001 { vel.y *= -1.0; vel.x *= 0.95; }
-		}
+		/* Rockets don't have materials, so bounce off solid edges with the default one */
+		world.boundary.apply( ( world.width, world.height ), atr.radius, &Material::default(), pos, vel,
+							  |side, crossed| crossings.push( Crossing { entity, side, crossed } ) );
 	});
+	crossings
 	
 }
 
-/// Generates the new population
+/// Distance from a position to the world's target, used as the fitness of a rocket
+pub fn distance_to_target(world: &World, pos: &Vec2) -> f32 {
+	((world.target_pos.x - pos.x).powf(2.0) + (world.target_pos.y - pos.y).powf(2.0)).sqrt()
+}
+
+/// Finds the rockets touching the target for the first time this generation, marking them as having reached it
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn target_system<P: Components<Vec2>, A: Components<Attributes>>(world: &World, pass: usize, pos_store: &P, atr_store: &mut A) -> Vec<TargetReached> {
+	let mut reached = Vec::new();
+	query((read(pos_store), write(atr_store))).for_each(|entity, (pos, atr)| {
+		/* A rocket staying on the target only reaches it once, until genetic_system breeds the next generation */
+		if !atr.reached && distance_to_target(world, pos) < TARGET_RADIUS + atr.radius {
+			atr.reached = true;
+			reached.push(TargetReached { entity, pass });
+		}
+	});
+	reached
+}
+
+/// Generates the new population, returning an error if there are no rockets with a position to breed from
+///
+/// The parents are picked in an order based only on the rockets' state, so the same seed breeds the same generation
+/// whatever order the gene store iterates in. Rockets closer to the target are more likely to be picked, but every rocket
+/// keeps a weight of at least 1, however far away it is
 ///
 /// # Examples
 ///
@@ -69,67 +103,118 @@ pub fn boundary_system<V: Store<Vec2>, P: Store<Vec2>, A: Store<Attributes>>(wor
 ///
 /// ```
 ///
-pub fn genetic_system<P: Store<Vec2>, V: Store<Vec2>, G: Store<[usize; 10]>>(world: &World,
-																		  pos_store: &mut P,
-																		  vel_store: &mut V,
-																		  gene_store: &mut G) {
+pub fn genetic_system<P: Components<Vec2>, V: Components<Vec2>, A: Components<Attributes>, G: Components<Vec<usize>>>(world: &World,
+																								rng: &mut SimRng,
+																								pos_store: &mut P,
+																								vel_store: &mut V,
+																								atr_store: &mut A,
+																								gene_store: &mut G) -> Result<(), WeightedError> {
 	
-	let mut weights: [(Entity, u32);10] = Default::default();
-	let mut i = 0;
+	let mut candidates: Vec<(Entity, u32, Vec2, Vec<usize>)> = Vec::with_capacity(gene_store.len());
 	
-	/* Calculates the entities distance from the target, and assigns a weight respectively */
-	gene_store.for_each( |entity, _| {
+	/* Calculates the entities distance from the target, and assigns a weight respectively. Distances are scaled by the
+	   arena's diagonal, the furthest a rocket inside it can be from the target, so tall narrow arenas still weight them */
+	let diagonal = world.width.hypot(world.height);
+	gene_store.for_each( |entity, dna| {
 		if let Some( pos ) = pos_store.get( entity ) {
-			let distance = ((world.target_pos.x - pos.x).powf(2.0) + (world.target_pos.y - pos.y).powf(2.0)).sqrt();
-			let weight = map(distance, 0.0..world.width.clone(), 100.0..0.0) as u32;
-			weights[i] = (entity, weight);
+			let distance = distance_to_target(world, pos);
+			let weight = map(distance, 0.0..diagonal, 100.0..1.0).max(1.0) as u32;
+			candidates.push((entity, weight, *pos, dna.clone()));
 		}
-		i = i + 1;
 	});
 	
+	/* Sorts the candidates by weight, dna and position, so rockets only tie when they are interchangeable */
+	candidates.sort_by(|a, b| {
+		b.1.cmp(&a.1)
+			.then_with(|| a.3.cmp(&b.3))
+			.then_with(|| a.2.x.total_cmp(&b.2.x))
+			.then_with(|| a.2.y.total_cmp(&b.2.y))
+	});
+	let weights: Vec<(Entity, u32)> = candidates.iter().map(|(entity, weight, _, _)| (*entity, *weight)).collect();
+	
 	/* Clones the dna of two randomly chosen entities based on their weights */
-	let (entity_a, entity_b) = choose_two(&weights).unwrap();
+	let (entity_a, entity_b) = choose_two(&weights, rng)?;
 	let dna_a = gene_store.get( *entity_a ).unwrap().clone();
 	let dna_b = gene_store.get( *entity_b ).unwrap().clone();
 	
 	/* Iterates through each entity and generates new dna */
 	gene_store.for_each_mut( |entity, dna| {
-		/* Resets the position of the rockets */
+		/* Resets the position of the rockets, and whether they have reached the target */
 		if let Some(pos) = pos_store.get_mut( entity ) {
-			*pos = world.default_pos.clone();
+			*pos = world.default_pos;
 		}
 		if let Some(vel) = vel_store.get_mut( entity ) {
-			*vel = world.default_vel.clone();
+			*vel = world.default_vel;
 		}
-		
-		/* Generates a temp dna */
-		let mut temp_dna: [usize; 10] = Default::default();
-		let mut i = 0;
-		/* Assigns each element of temp_dna the value of either dna_a or dna_b's element at the same index */
-		for gene in &mut temp_dna {
-			let mut rng = thread_rng();
-			
-			match rng.gen_bool(0.5) {
-				true => {
-					*gene = dna_a[i];
-				},
-				false => {
-					*gene = dna_b[i];
-				}
-			}
-			i = i + 1;
+		if let Some(atr) = atr_store.get_mut( entity ) {
+			atr.reached = false;
 		}
+		
+		/* Generates a temp dna, assigning each gene the value of either dna_a or dna_b's gene at the same index */
+		let mut temp_dna: Vec<usize> = dna_a.iter().zip(dna_b.iter())
+			.map(|(gene_a, gene_b)| if rng.chance(0.5) { *gene_a } else { *gene_b })
+			.collect();
+		
 		/* If picks true, mutate one gene in dna */
-		if thread_rng().gen_bool(world.mutation_chance) {
-			temp_dna[random(0..10)] = random(0..10);
+		if !temp_dna.is_empty() && rng.chance(world.mutation_chance) {
+			let len = temp_dna.len();
+			temp_dna[rng.range(0..len)] = rng.range(0..THRUSTS);
 		}
 		/* Assigns entities dna the newly generated dna */
 		*dna = temp_dna;
 	});
+	Ok(())
+}
+
+/// Number of different thrusts a gene can pick from
+pub const THRUSTS: usize = 10;
+
+/// Sets acc to the thrust picked by gene
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn thrust(gene: usize, world: &World, acc: &mut Vec2) {
+	match gene % THRUSTS {
+		0 => acc.lerp(&world.target_pos, UnitF::new(0.03)),
+		1 => *acc = Vec2::new(&15.5, &15.5),
+		2 => *acc = Vec2::new(&0.0, &-14.4),
+		3 => *acc = Vec2::new(&-17.5, &0.0),
+		4 => *acc = Vec2::new(&-16.5, &14.0),
+		5 => *acc = Vec2::new(&0.0, &-18.4),
+		6 => *acc = Vec2::new(&18.5, &0.0),
+		7 => *acc = Vec2::new(&12.3, &-18.4),
+		8 => *acc = Vec2::new(&-12.5, &0.0),
+		_ => *acc = Vec2::new(&0.0, &12.4),
+	}
+}
+
+/// Applies the thrust each entities dna picks for the current pass to its acceleration
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+///
+pub fn thrust_system<G: Components<Vec<usize>>, A: Components<Vec2>>(world: &World,
+														   pass: usize,
+														   gene_store: &G,
+														   acc_store: &mut A) {
+	gene_store.for_each(|entity, dna| {
+		if let Some(acc) = acc_store.get_mut(entity) {
+			if !dna.is_empty() {
+				thrust(dna[pass % dna.len()], world, acc);
+			}
+		}
+	});
 }
 
 /// Moves entities based on their acceleration and velocity
-pub fn movement_system<P: Store<Vec2>, V: Store<Vec2>, A: Store<Vec2>, M: Store<f32>>(pos_store: &mut P,
+pub fn movement_system<P: Components<Vec2>, V: Components<Vec2>, A: Components<Vec2>, M: Components<f32>>(pos_store: &mut P,
 																					  vel_store: &mut V,
 																					  acc_store: &mut A,
 																					  mass_store: &M) {
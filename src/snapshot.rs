@@ -0,0 +1,155 @@
+//! Saving and restoring sketch state, used to record, replay and rewind simulations.
+//!
+//! Component stores are saved as lists of (entity id, component) sorted by id. misc_ecs only hands out new random entity
+//! ids, so restoring creates new entities and maps each saved id to its new entity.
+
+use std::collections::HashMap;
+use std::io;
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::store::Components;
+
+/// A component which can be saved in a snapshot, by converting it to a serialisable form
+pub trait Snap: Sized {
+	/// The form the component is saved in
+	type Repr: Serialize + DeserializeOwned;
+
+	fn to_repr( &self ) -> Self::Repr;
+
+	fn from_repr( repr: Self::Repr ) -> Self;
+}
+
+impl Snap for Vec2 {
+	type Repr = [f32; 2];
+
+	fn to_repr( &self ) -> Self::Repr {
+		[self.x, self.y]
+	}
+
+	fn from_repr( [x, y]: Self::Repr ) -> Self {
+		Vec2::new( &x, &y )
+	}
+}
+
+/// Components which are already serialisable are saved as they are
+macro_rules! snap_as_is {
+	( $( $t:ty ),* ) => {
+		$( impl Snap for $t {
+			type Repr = $t;
+
+			fn to_repr( &self ) -> Self::Repr {
+				self.clone()
+			}
+
+			fn from_repr( repr: Self::Repr ) -> Self {
+				repr
+			}
+		} )*
+	};
+}
+
+snap_as_is!( f32, Vec<usize>, crate::material::Material, crate::shape::Shape, crate::bouncy_balls::data::Attributes, crate::smart_rockets::data::Attributes );
+
+/// Every component in a store, with the id of the entity it belongs to
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use misc_ecs::prelude::*;
+/// use rusty_nature_of_code::snapshot::*;
+///
+/// let mut entity_manager = EntityManager::new();
+/// let mut pos_store = HashStore::new();
+/// let entity = entity_manager.next();
+/// pos_store.add( entity, Vec2::new( &1.0, &2.0 ) );
+///
+/// let saved = StoreSnapshot::save( &pos_store );
+///
+/// let ( _entity_manager, entities ) = restore_entities( &saved.ids() );
+/// let mut restored: HashStore<Vec2> = HashStore::new();
+/// saved.restore( &entities, &mut restored );
+///
+/// assert_eq!( *restored.get( entities[&entity.id()] ).unwrap(), Vec2::new( &1.0, &2.0 ) );
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoreSnapshot<R> {
+	pub entries: Vec<( u64, R )>,
+}
+
+impl<R> StoreSnapshot<R> {
+	/// Saves every component in store
+	pub fn save<T: Snap<Repr = R>, S: Components<T>>( store: &S ) -> StoreSnapshot<R> {
+		let mut entries = Vec::with_capacity( store.len() );
+		store.for_each( |entity, t| entries.push( ( entity.id(), t.to_repr() ) ) );
+		entries.sort_by_key( |( id, _ )| *id );
+		StoreSnapshot { entries }
+	}
+
+	/// The ids of the entities which have a component
+	pub fn ids( &self ) -> Vec<u64> {
+		self.entries.iter().map( |( id, _ )| *id ).collect()
+	}
+
+	/// Adds each component to store, on the entity its saved id maps to. Components of unmapped entities are skipped
+	pub fn restore<T: Snap<Repr = R>, S: Components<T>>( self, entities: &HashMap<u64, Entity>, store: &mut S ) {
+		for ( id, repr ) in self.entries {
+			if let Some( entity ) = entities.get( &id ) {
+				store.add( *entity, T::from_repr( repr ) );
+			}
+		}
+	}
+}
+
+/// Creates a new EntityManager with an entity for each saved id, returning it and a map from saved ids to the new entities
+pub fn restore_entities( ids: &[u64] ) -> ( EntityManager, HashMap<u64, Entity> ) {
+	let mut entity_manager = EntityManager::new();
+	let mut entities = HashMap::new();
+	for id in ids {
+		entities.entry( *id ).or_insert_with( || entity_manager.next() );
+	}
+	( entity_manager, entities )
+}
+
+/// Encodes state as compact binary
+pub fn encode<T: Serialize>( state: &T ) -> Vec<u8> {
+	bincode::serialize( state ).expect( "sketch state can always be encoded" )
+}
+
+/// Decodes state written by encode
+pub fn decode<T: DeserializeOwned>( bytes: &[u8] ) -> io::Result<T> {
+	bincode::deserialize( bytes ).map_err( |e| io::Error::new( io::ErrorKind::InvalidData, e ) )
+}
+
+/// Serialises a Vec2 field as [x, y], use with #[serde(with = "crate::snapshot::vec2")]
+pub mod vec2 {
+	use miscmath::prelude::Vec2;
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	pub fn serialize<S: Serializer>( vec: &Vec2, serializer: S ) -> Result<S::Ok, S::Error> {
+		[vec.x, vec.y].serialize( serializer )
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>( deserializer: D ) -> Result<Vec2, D::Error> {
+		let [x, y] = <[f32; 2]>::deserialize( deserializer )?;
+		Ok( Vec2::new( &x, &y ) )
+	}
+}
+
+/// Serialises a list of Vec2s as [[x, y], ...], use with #[serde(with = "crate::snapshot::vec2s")]
+pub mod vec2s {
+	use miscmath::prelude::Vec2;
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	pub fn serialize<S: Serializer>( vecs: &[Vec2], serializer: S ) -> Result<S::Ok, S::Error> {
+		vecs.iter().map( |vec| [vec.x, vec.y] ).collect::<Vec<_>>().serialize( serializer )
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>( deserializer: D ) -> Result<Vec<Vec2>, D::Error> {
+		let points = Vec::<[f32; 2]>::deserialize( deserializer )?;
+		Ok( points.into_iter().map( |[x, y]| Vec2::new( &x, &y ) ).collect() )
+	}
+}
@@ -0,0 +1,370 @@
+//! A dense component store, for the stores hot loops like movement and collision go through.
+//!
+//! misc_ecs's HashStore keeps components in a HashMap keyed by Entity, so each get is a SipHash of the entity and each
+//! for_each walks the map's buckets. DenseStore keeps the components packed in a Vec, a sparse set, and looks entities
+//! up with a map from id to index which doesn't rehash the ids, as they are already random.
+//!
+//! Systems take their stores through the Components trait, which both implement. It's misc_ecs's Store trait without
+//! iter and iter_mut, which return std's hash_map::Iter and IterMut. Those can only borrow a HashMap, so a DenseStore
+//! could only implement Store by panicking in them, and a system calling iter would compile and then fail at runtime.
+//!
+//! This changes the bounds of the public systems from Store to Components. Calls passing HashStores are unchanged, as
+//! HashStore implements Components, but generic code bounded by Store has to either change its bound to Components, or
+//! wrap its stores in a StoreAdapter. Components isn't in the preludes, as calling a method on a HashStore with both
+//! traits in scope is ambiguous.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use misc_ecs::prelude::*;
+
+/// Hasher which passes an entity id through unchanged. Entity ids are random u64s, so they are already spread evenly
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IdHasher( u64 );
+
+impl Hasher for IdHasher {
+	fn finish( &self ) -> u64 {
+		self.0
+	}
+
+	fn write( &mut self, bytes: &[u8] ) {
+		/* Only reached for keys other than u64s, which this hasher isn't used for, but still mixes every byte in */
+		for byte in bytes {
+			self.0 = self.0.rotate_left( 8 ) ^ *byte as u64;
+		}
+	}
+
+	fn write_u64( &mut self, id: u64 ) {
+		self.0 = id;
+	}
+}
+
+/// A store of components of type T, one per entity, which systems are generic over
+///
+/// misc_ecs's HashStore is one too, so systems can be run over either.
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use misc_ecs::prelude::{EntityManager, HashStore, Storage};
+/// use rusty_nature_of_code::store::*;
+///
+/// /* Sums the x of every position, whichever store they are in */
+/// fn total_x<P: Components<Vec2>>( pos_store: &P ) -> f32 {
+///     let mut total = 0.0;
+///     pos_store.for_each( |_, pos| total += pos.x );
+///     total
+/// }
+///
+/// let mut entity_manager = EntityManager::new();
+/// let ( mut hash_store, mut dense_store ) = ( HashStore::new(), DenseStore::new() );
+/// for x in [ 1.0, 2.0, 3.0 ] {
+///     let entity = entity_manager.next();
+///     hash_store.add( entity, Vec2::new( &x, &0.0 ) );
+///     dense_store.add( entity, Vec2::new( &x, &0.0 ) );
+/// }
+///
+/// assert_eq!( total_x( &hash_store ), 6.0 );
+/// assert_eq!( total_x( &dense_store ), 6.0 );
+/// ```
+///
+pub trait Components<T> {
+	fn add( &mut self, entity: Entity, t: T );
+	fn get( &self, entity: Entity ) -> Option<&T>;
+	fn get_mut( &mut self, entity: Entity ) -> Option<&mut T>;
+	fn drop( &mut self, entity: Entity );
+	fn for_each<F: FnMut( Entity, &T )>( &self, func: F );
+	fn for_each_mut<F: FnMut( Entity, &mut T )>( &mut self, func: F );
+	fn len( &self ) -> usize;
+
+	/// Whether the store has no components
+	fn is_empty( &self ) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<T> Components<T> for HashStore<T> {
+	fn add( &mut self, entity: Entity, t: T ) {
+		Store::add( self, entity, t )
+	}
+
+	fn get( &self, entity: Entity ) -> Option<&T> {
+		Store::get( self, entity )
+	}
+
+	fn get_mut( &mut self, entity: Entity ) -> Option<&mut T> {
+		Store::get_mut( self, entity )
+	}
+
+	fn drop( &mut self, entity: Entity ) {
+		Store::drop( self, entity )
+	}
+
+	fn for_each<F: FnMut( Entity, &T )>( &self, func: F ) {
+		Store::for_each( self, func )
+	}
+
+	fn for_each_mut<F: FnMut( Entity, &mut T )>( &mut self, func: F ) {
+		Store::for_each_mut( self, func )
+	}
+
+	fn len( &self ) -> usize {
+		Store::len( self )
+	}
+}
+
+/// Any of misc_ecs's stores, passed to a system or query taking Components
+///
+/// HashStore already implements Components, so this is only needed for other stores, or in code generic over Store.
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use misc_ecs::prelude::*;
+/// use rusty_nature_of_code::store::{Components, StoreAdapter};
+///
+/// fn total_x<P: Components<Vec2>>( pos_store: &P ) -> f32 {
+///     let mut total = 0.0;
+///     pos_store.for_each( |_, pos| total += pos.x );
+///     total
+/// }
+///
+/// /* Code written against misc_ecs's Store can still call it */
+/// fn report<S: Store<Vec2>>( pos_store: &mut S ) -> f32 {
+///     total_x( &StoreAdapter( pos_store ) )
+/// }
+///
+/// /* With both traits in scope, HashStore's methods have to name the trait */
+/// let mut pos_store = HashStore::new();
+/// Store::add( &mut pos_store, EntityManager::new().next(), Vec2::new( &2.0, &0.0 ) );
+/// assert_eq!( report( &mut pos_store ), 2.0 );
+/// ```
+///
+pub struct StoreAdapter<'a, S>( pub &'a mut S );
+
+impl<T, S: Store<T>> Components<T> for StoreAdapter<'_, S> {
+	fn add( &mut self, entity: Entity, t: T ) {
+		self.0.add( entity, t )
+	}
+
+	fn get( &self, entity: Entity ) -> Option<&T> {
+		self.0.get( entity )
+	}
+
+	fn get_mut( &mut self, entity: Entity ) -> Option<&mut T> {
+		self.0.get_mut( entity )
+	}
+
+	fn drop( &mut self, entity: Entity ) {
+		self.0.drop( entity )
+	}
+
+	fn for_each<F: FnMut( Entity, &T )>( &self, func: F ) {
+		self.0.for_each( func )
+	}
+
+	fn for_each_mut<F: FnMut( Entity, &mut T )>( &mut self, func: F ) {
+		self.0.for_each_mut( func )
+	}
+
+	fn len( &self ) -> usize {
+		self.0.len()
+	}
+}
+
+/// Components packed in a Vec in the order they were added, with a map from entity id to index
+///
+/// Dropping a component moves the last one into its place, so iteration order only depends on the order components were
+/// added and dropped.
+///
+/// It implements Components rather than misc_ecs's Store, as it has no HashMap to iterate. Use for_each, for_each_mut or
+/// a query instead of iter, as every system in this crate does.
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use misc_ecs::prelude::EntityManager;
+/// use rusty_nature_of_code::store::*;
+///
+/// let mut entity_manager = EntityManager::new();
+/// let mut pos_store = DenseStore::new();
+/// let ( a, b, c ) = ( entity_manager.next(), entity_manager.next(), entity_manager.next() );
+/// pos_store.add( a, Vec2::new( &1.0, &0.0 ) );
+/// pos_store.add( b, Vec2::new( &2.0, &0.0 ) );
+/// pos_store.add( c, Vec2::new( &3.0, &0.0 ) );
+///
+/// /* c moves into a's place */
+/// pos_store.drop( a );
+/// pos_store.for_each_mut( |_, pos| pos.y = 1.0 );
+///
+/// assert_eq!( pos_store.len(), 2 );
+/// assert_eq!( pos_store.get( a ), None );
+/// assert_eq!( *pos_store.get( c ).unwrap(), Vec2::new( &3.0, &1.0 ) );
+/// assert_eq!( pos_store.entities(), [ c, b ] );
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct DenseStore<T> {
+	entities: Vec<Entity>,
+	items: Vec<T>,
+	index: HashMap<u64, usize, BuildHasherDefault<IdHasher>>,
+}
+
+impl<T> Default for DenseStore<T> {
+	fn default() -> Self {
+		DenseStore::new()
+	}
+}
+
+impl<T> Storage for DenseStore<T> {
+	fn new() -> Self {
+		DenseStore::new()
+	}
+}
+
+impl<T> DenseStore<T> {
+	pub fn new() -> DenseStore<T> {
+		DenseStore { entities: Vec::new(), items: Vec::new(), index: HashMap::default() }
+	}
+
+	/// Creates an empty store with room for capacity components
+	pub fn with_capacity( capacity: usize ) -> DenseStore<T> {
+		DenseStore { entities: Vec::with_capacity( capacity ), items: Vec::with_capacity( capacity ), index: HashMap::with_capacity_and_hasher( capacity, Default::default() ) }
+	}
+
+	/// The entities with a component, in the order their components are stored
+	pub fn entities( &self ) -> &[Entity] {
+		&self.entities
+	}
+
+	/// The components, in the same order as entities
+	pub fn items( &self ) -> &[T] {
+		&self.items
+	}
+
+	/// The components, in the same order as entities, to change
+	pub fn items_mut( &mut self ) -> &mut [T] {
+		&mut self.items
+	}
+
+	/// The entities, and their components to change, at once
+	pub fn parts_mut( &mut self ) -> ( &[Entity], &mut [T] ) {
+		( &self.entities, &mut self.items )
+	}
+
+	/// Reorders the components so the entities are in the same order as order, e.g. another store's entities, so the two
+	/// stores' items can be zipped together
+	///
+	/// Returns false, leaving the store as it was, if it doesn't hold exactly the entities in order.
+	pub fn align( &mut self, order: &[Entity] ) -> bool {
+		if self.entities == order {
+			return true;
+		}
+		if self.entities.len() != order.len() {
+			return false;
+		}
+
+		/* Finds where each entity's component is now, giving up if the store doesn't have one of them, or order has it twice */
+		let mut from = Vec::with_capacity( order.len() );
+		let mut taken = vec![ false; order.len() ];
+		for entity in order {
+			match self.index.get( &entity.id() ) {
+				Some( &i ) if self.entities[i] == *entity && !taken[i] => {
+					taken[i] = true;
+					from.push( i );
+				}
+				_ => return false,
+			}
+		}
+
+		let mut items: Vec<Option<T>> = self.items.drain( .. ).map( Some ).collect();
+		self.items = from.iter().map( |&i| items[i].take().expect( "each entity is only in order once" ) ).collect();
+		self.entities = order.to_vec();
+		for ( i, entity ) in self.entities.iter().enumerate() {
+			self.index.insert( entity.id(), i );
+		}
+		true
+	}
+}
+
+impl<T: Send + Sync> DenseStore<T> {
+	/// Runs func on each entity and its component, split across rayon's threads with the parallel feature, and one after
+	/// another without it
+	pub fn par_for_each<F: Fn( Entity, &T ) + Sync + Send>( &self, func: F ) {
+		#[cfg(feature = "parallel")]
+		{
+			use rayon::prelude::*;
+			self.entities.par_iter().zip( self.items.par_iter() ).for_each( |( entity, t )| func( *entity, t ) );
+		}
+		#[cfg(not(feature = "parallel"))]
+		self.for_each( func );
+	}
+
+	/// Runs func on each entity and its component to change, split across rayon's threads with the parallel feature, and
+	/// one after another without it
+	pub fn par_for_each_mut<F: Fn( Entity, &mut T ) + Sync + Send>( &mut self, func: F ) {
+		#[cfg(feature = "parallel")]
+		{
+			use rayon::prelude::*;
+			self.entities.par_iter().zip( self.items.par_iter_mut() ).for_each( |( entity, t )| func( *entity, t ) );
+		}
+		#[cfg(not(feature = "parallel"))]
+		self.for_each_mut( func );
+	}
+}
+
+impl<T> Components<T> for DenseStore<T> {
+	fn add( &mut self, entity: Entity, t: T ) {
+		match self.index.get( &entity.id() ) {
+			Some( &i ) => {
+				self.entities[i] = entity;
+				self.items[i] = t;
+			}
+			None => {
+				self.index.insert( entity.id(), self.items.len() );
+				self.entities.push( entity );
+				self.items.push( t );
+			}
+		}
+	}
+
+	fn get( &self, entity: Entity ) -> Option<&T> {
+		/* HashStore keys on the whole entity, so an entity which has been dropped doesn't match its active copy */
+		let i = *self.index.get( &entity.id() )?;
+		( self.entities[i] == entity ).then( || &self.items[i] )
+	}
+
+	fn get_mut( &mut self, entity: Entity ) -> Option<&mut T> {
+		let i = *self.index.get( &entity.id() )?;
+		( self.entities[i] == entity ).then( || &mut self.items[i] )
+	}
+
+	fn drop( &mut self, entity: Entity ) {
+		let Some( i ) = self.index.remove( &entity.id() ) else {
+			return;
+		};
+		self.entities.swap_remove( i );
+		self.items.swap_remove( i );
+		if let Some( moved ) = self.entities.get( i ) {
+			self.index.insert( moved.id(), i );
+		}
+	}
+
+	fn for_each<F: FnMut( Entity, &T )>( &self, mut func: F ) {
+		for ( entity, t ) in self.entities.iter().zip( self.items.iter() ) {
+			func( *entity, t );
+		}
+	}
+
+	fn for_each_mut<F: FnMut( Entity, &mut T )>( &mut self, mut func: F ) {
+		for ( entity, t ) in self.entities.iter().zip( self.items.iter_mut() ) {
+			func( *entity, t );
+		}
+	}
+
+	fn len( &self ) -> usize {
+		self.items.len()
+	}
+}
@@ -0,0 +1,364 @@
+//! A World owns the entities of a sketch, a store for each kind of component, and singleton resources such as the
+//! collision grid, so systems can be handed one World instead of every store they touch.
+//!
+//! Components are named by marker types implementing Component, so stores holding the same data, like positions and
+//! velocities, are still told apart. Stores and resources sit in RefCells: a system can borrow several at once through a
+//! shared World, and borrowing one mutably while it's already borrowed panics, naming the store.
+//!
+//! Entities are spawned with a Bundle of their components and despawned with World::despawn, which removes them from
+//! every store and runs the despawn hooks, e.g. to take them out of the collision grid.
+//!
+//! Systems tell each other what happened by sending events, see the events module.
+//!
+//! The screen size, random number generator and config stay in the Context the runner passes every system, as the
+//! runner, recorder and time controls use them between passes.
+
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use crate::events::{Events, Spawned};
+use crate::schedule::{Condition, Stage, System};
+use crate::snapshot;
+use crate::store::{Components, DenseStore};
+
+/// Names a kind of component, and the data each entity's component holds
+pub trait Component: 'static {
+	type Data: 'static;
+}
+
+/// Declares marker types implementing Component, e.g. `component!( Pos => Vec2 );`
+#[macro_export]
+macro_rules! component {
+	( $( $( #[$meta:meta] )* $name:ident => $data:ty ),* $(,)? ) => {
+		$(
+			$( #[$meta] )*
+			#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+			pub struct $name;
+
+			impl $crate::world::Component for $name {
+				type Data = $data;
+			}
+		)*
+	};
+}
+
+component!(
+	/// Position in world coordinates, where (0,0) is the bottom left of the screen
+	Pos => Vec2,
+	/// Velocity, added to the position each pass
+	Vel => Vec2,
+	/// Acceleration, added to the velocity each pass
+	Acc => Vec2,
+	/// Mass, which scales the forces applied to an entity
+	Mass => f32,
+);
+
+/// Components added to an entity together when it's spawned
+pub trait Bundle {
+	/// Adds each component to its store
+	fn insert( self, world: &World, entity: Entity );
+}
+
+/// A store of component C, with the operations World needs on every store whatever its component
+trait AnyStore {
+	fn as_any( &self ) -> &dyn Any;
+	/// Name of the component, for messages
+	fn component( &self ) -> &'static str;
+	fn drop_entity( &self, entity: Entity );
+	fn entities( &self ) -> Vec<Entity>;
+	fn clear( &self );
+}
+
+struct ComponentStore<C: Component> {
+	store: RefCell<DenseStore<C::Data>>,
+	component: PhantomData<C>,
+}
+
+impl<C: Component> ComponentStore<C> {
+	fn borrow_mut( &self ) -> RefMut<'_, DenseStore<C::Data>> {
+		self.store.try_borrow_mut().unwrap_or_else( |_| panic!( "store of {} is already borrowed", type_name::<C>() ) )
+	}
+}
+
+impl<C: Component> AnyStore for ComponentStore<C> {
+	fn as_any( &self ) -> &dyn Any {
+		self
+	}
+
+	fn component( &self ) -> &'static str {
+		type_name::<C>()
+	}
+
+	fn drop_entity( &self, entity: Entity ) {
+		self.borrow_mut().drop( entity );
+	}
+
+	fn entities( &self ) -> Vec<Entity> {
+		self.store.borrow().entities().to_vec()
+	}
+
+	fn clear( &self ) {
+		*self.borrow_mut() = DenseStore::new();
+	}
+}
+
+/// The EntityManager, and the entities it has handed out which haven't been despawned
+struct Entities {
+	manager: EntityManager,
+	live: HashMap<u64, Entity>,
+}
+
+/// Entities, their components and resources
+///
+/// # Examples
+///
+/// ```
+/// use miscmath::prelude::*;
+/// use rusty_nature_of_code::store::Components;
+/// use rusty_nature_of_code::world::*;
+///
+/// let mut world = World::new();
+/// world.register::<Pos>().register::<Vel>();
+/// world.insert_resource( 9.8_f32 );
+///
+/// let entity = world.spawn( ( Pos, Vec2::new( &0.0, &10.0 ) ) );
+/// world.store_mut::<Vel>().add( entity, Vec2::new( &1.0, &0.0 ) );
+///
+/// /* Positions and velocities are both Vec2s, but live in different stores, so both can be borrowed at once */
+/// {
+///     let vel = world.store::<Vel>();
+///     world.store_mut::<Pos>().for_each_mut( |entity, pos| pos.add( vel.get( entity ).unwrap() ) );
+/// }
+///
+/// assert_eq!( *world.store::<Pos>().get( entity ).unwrap(), Vec2::new( &1.0, &10.0 ) );
+/// assert_eq!( *world.resource::<f32>(), 9.8 );
+///
+/// /* Despawning removes the entity from every store */
+/// world.despawn( entity );
+/// assert!( world.is_empty() );
+/// assert_eq!( world.store::<Vel>().len(), 0 );
+///
+/// /* A component added to an entity which isn't live is an orphan */
+/// world.store_mut::<Vel>().add( entity, Vec2::default() );
+/// assert_eq!( world.orphans().len(), 1 );
+/// ```
+///
+pub struct World {
+	entities: RefCell<Entities>,
+	/// The store of each registered component
+	stores: HashMap<TypeId, Box<dyn AnyStore>>,
+	/// Boxed RefCell<R> for each resource R
+	resources: HashMap<TypeId, Box<dyn Any>>,
+	/// Run on each entity despawned, before its components are removed
+	despawn_hooks: Vec<fn( &World, Entity )>,
+	/// Empties the queue of each event type added
+	event_clears: Vec<fn( &World )>,
+}
+
+impl Default for World {
+	fn default() -> Self {
+		World::new()
+	}
+}
+
+impl World {
+	/// Creates a world with no entities, components or resources
+	pub fn new() -> World {
+		World {
+			entities: RefCell::new( Entities { manager: EntityManager::new(), live: HashMap::new() } ),
+			stores: HashMap::new(),
+			resources: HashMap::new(),
+			despawn_hooks: Vec::new(),
+			event_clears: Vec::new(),
+		}
+	}
+
+	/// Adds an empty store for component C, unless it already has one
+	pub fn register<C: Component>( &mut self ) -> &mut World {
+		self.stores.entry( TypeId::of::<C>() ).or_insert_with( || Box::new( ComponentStore::<C> { store: RefCell::new( DenseStore::new() ), component: PhantomData } ) );
+		self
+	}
+
+	/// Whether component C has a store
+	pub fn has<C: Component>( &self ) -> bool {
+		self.stores.contains_key( &TypeId::of::<C>() )
+	}
+
+	fn component_store<C: Component>( &self ) -> &ComponentStore<C> {
+		self.stores.get( &TypeId::of::<C>() )
+			.and_then( |store| store.as_any().downcast_ref() )
+			.unwrap_or_else( || panic!( "component {} isn't registered", type_name::<C>() ) )
+	}
+
+	/// The store of component C. Panics if it isn't registered or is borrowed mutably
+	pub fn store<C: Component>( &self ) -> Ref<'_, DenseStore<C::Data>> {
+		self.component_store::<C>().store.try_borrow().unwrap_or_else( |_| panic!( "store of {} is already borrowed mutably", type_name::<C>() ) )
+	}
+
+	/// The store of component C, to change. Panics if it isn't registered or is already borrowed
+	pub fn store_mut<C: Component>( &self ) -> RefMut<'_, DenseStore<C::Data>> {
+		self.component_store::<C>().borrow_mut()
+	}
+
+	/// Creates an entity with the components in bundle, sending a Spawned event if the world has added them
+	pub fn spawn<B: Bundle>( &self, bundle: B ) -> Entity {
+		let entity = {
+			let mut entities = self.entities.borrow_mut();
+			let entity = entities.manager.next();
+			entities.live.insert( entity.id(), entity );
+			entity
+		};
+		bundle.insert( self, entity );
+		if self.has_resource::<Events<Spawned>>() {
+			self.send( Spawned { entity } );
+		}
+		entity
+	}
+
+	/// Runs the despawn hooks on entity, then removes it from every store and drops it. Does nothing if it isn't live, so
+	/// an entity found more than once by a system can be despawned more than once
+	///
+	/// Panics if a store is borrowed.
+	pub fn despawn( &self, entity: Entity ) {
+		if !self.contains( entity ) {
+			return;
+		}
+		for hook in self.despawn_hooks.iter() {
+			hook( self, entity );
+		}
+		for store in self.stores.values() {
+			store.drop_entity( entity );
+		}
+		let mut entities = self.entities.borrow_mut();
+		entities.live.remove( &entity.id() );
+		entities.manager.drop( &mut entity.clone() );
+	}
+
+	/// Adds a function run on each entity despawned, before its components are removed, e.g. to remove it from a spatial index
+	pub fn on_despawn( &mut self, hook: fn( &World, Entity ) ) -> &mut World {
+		self.despawn_hooks.push( hook );
+		self
+	}
+
+	/// Whether entity has been spawned and not despawned
+	pub fn contains( &self, entity: Entity ) -> bool {
+		self.entities.borrow().live.contains_key( &entity.id() )
+	}
+
+	/// Number of live entities
+	pub fn len( &self ) -> usize {
+		self.entities.borrow().live.len()
+	}
+
+	pub fn is_empty( &self ) -> bool {
+		self.len() == 0
+	}
+
+	/// Replaces every entity with ones with the given ids, e.g. those saved in a snapshot, emptying every store and event
+	/// queue. Returns the new entities by id, for StoreSnapshot::restore
+	pub fn restore_entities( &mut self, ids: &[u64] ) -> HashMap<u64, Entity> {
+		let ( manager, live ) = snapshot::restore_entities( ids );
+		*self.entities.borrow_mut() = Entities { manager, live: live.clone() };
+		for store in self.stores.values() {
+			store.clear();
+		}
+		self.clear_events();
+		live
+	}
+
+	/// Components belonging to entities which aren't live, by component name. These are left by removing an entity some
+	/// other way than despawn, and would be picked up by the next entity given the same id
+	pub fn orphans( &self ) -> Vec<( &'static str, Entity )> {
+		let entities = self.entities.borrow();
+		let mut orphans: Vec<_> = self.stores.values()
+			.flat_map( |store| store.entities().into_iter().map( |entity| ( store.component(), entity ) ) )
+			.filter( |( _, entity )| !entities.live.contains_key( &entity.id() ) )
+			.collect();
+		orphans.sort_by_key( |( component, entity )| ( *component, entity.id() ) );
+		orphans
+	}
+
+	/// Adds an empty queue for events of type E as a resource, unless it already has one
+	pub fn add_event<E: 'static>( &mut self ) -> &mut World {
+		if !self.has_resource::<Events<E>>() {
+			self.insert_resource( Events::<E>::new() );
+			self.event_clears.push( |world| world.resource_mut::<Events<E>>().clear() );
+		}
+		self
+	}
+
+	/// Sends an event. Panics if E hasn't been added
+	pub fn send<E: 'static>( &self, event: E ) {
+		self.resource_mut::<Events<E>>().send( event );
+	}
+
+	/// The events of type E sent since the queues were last cleared. Panics if E hasn't been added
+	pub fn events<E: 'static>( &self ) -> Ref<'_, Events<E>> {
+		self.resource()
+	}
+
+	/// Empties the queue of every event type
+	pub fn clear_events( &self ) {
+		for clear in self.event_clears.iter() {
+			clear( self );
+		}
+	}
+
+	/// Adds or replaces the resource of type R, returning the one it replaced
+	pub fn insert_resource<R: 'static>( &mut self, resource: R ) -> Option<R> {
+		self.resources.insert( TypeId::of::<R>(), Box::new( RefCell::new( resource ) ) )
+			.and_then( |old| old.downcast::<RefCell<R>>().ok() )
+			.map( |old| old.into_inner() )
+	}
+
+	/// Removes the resource of type R
+	pub fn remove_resource<R: 'static>( &mut self ) -> Option<R> {
+		self.resources.remove( &TypeId::of::<R>() )
+			.and_then( |old| old.downcast::<RefCell<R>>().ok() )
+			.map( |old| old.into_inner() )
+	}
+
+	/// Whether there is a resource of type R
+	pub fn has_resource<R: 'static>( &self ) -> bool {
+		self.resources.contains_key( &TypeId::of::<R>() )
+	}
+
+	fn resource_cell<R: 'static>( &self ) -> &RefCell<R> {
+		self.resources.get( &TypeId::of::<R>() )
+			.and_then( |resource| resource.downcast_ref() )
+			.unwrap_or_else( || panic!( "there is no {} resource", type_name::<R>() ) )
+	}
+
+	/// The resource of type R. Panics if there isn't one or it's borrowed mutably
+	pub fn resource<R: 'static>( &self ) -> Ref<'_, R> {
+		self.resource_cell::<R>().try_borrow().unwrap_or_else( |_| panic!( "resource {} is already borrowed mutably", type_name::<R>() ) )
+	}
+
+	/// The resource of type R, to change. Panics if there isn't one or it's already borrowed
+	pub fn resource_mut<R: 'static>( &self ) -> RefMut<'_, R> {
+		self.resource_cell::<R>().try_borrow_mut().unwrap_or_else( |_| panic!( "resource {} is already borrowed", type_name::<R>() ) )
+	}
+}
+
+/// A single component is a bundle of one, given as the component and its data
+impl<C: Component> Bundle for ( C, C::Data ) {
+	fn insert( self, world: &World, entity: Entity ) {
+		world.store_mut::<C>().add( entity, self.1 );
+	}
+}
+
+/// Passes between orphan checks. Each check collects the entities of every store, too slow to run every pass with many
+/// entities, and an orphan left behind is still there to be found a few passes later
+pub const ORPHAN_CHECK_PASSES: usize = 60;
+
+/// Panics if any component has been left behind by an entity which isn't live. Only runs in debug builds, every
+/// ORPHAN_CHECK_PASSES passes
+pub fn orphan_check() -> System<World> {
+	System::new( "orphan_check", Stage::Cleanup, |world: &mut World, _ctx| {
+		let orphans = world.orphans();
+		assert!( orphans.is_empty(), "components left by entities which aren't live: {:?}", orphans );
+	}).run_if( Condition::If( |ctx| cfg!( debug_assertions ) && ctx.frame.is_multiple_of( ORPHAN_CHECK_PASSES ) ) )
+}
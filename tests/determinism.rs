@@ -0,0 +1,154 @@
+//! Seeded simulations run twice, which should end in the same state with the same stats every time.
+
+use rusty_nature_of_code::canvas::{DrawCommand, RecordingCanvas};
+use rusty_nature_of_code::sketch::{step, Context, Sketch};
+use rusty_nature_of_code::sketches::BouncyBalls;
+use rusty_nature_of_code::rng::SimRng;
+use rusty_nature_of_code::smart_rockets::headless::*;
+
+/// Passes each bouncy balls run is stepped for, long enough for the balls to land, collide and be replaced
+const PASSES: usize = 300;
+
+#[test]
+fn headless_rockets_repeat() {
+	let config = RunConfig { seed: 7, generations: 10, ..Default::default() };
+
+	let first = run( &config ).unwrap();
+	let second = run( &config ).unwrap();
+	assert_eq!( first.len(), 10 );
+	assert_eq!( first, second );
+}
+
+/// Steps a bouncy balls sketch seeded with seed, returning its HUD stats and what it drew after each pass
+///
+/// misc_ecs gives entities random ids, so saved states differ between runs however the balls move. The debug overlay
+/// draws each ball's velocity and acceleration as well as its position, shape and color, so the state is compared through
+/// what is drawn instead, without the id labels.
+fn bouncy_balls( seed: u64 ) -> ( Vec<Vec<String>>, Vec<Vec<DrawCommand>> ) {
+	let mut ctx = Context::new( ( 640, 480 ), SimRng::new( seed ) );
+	/* The HUD only shows the collision, wall hit, wrap and despawn counts in debug */
+	ctx.debug = true;
+	let mut sketch = BouncyBalls::new();
+	sketch.setup( &mut ctx );
+
+	let mut canvas = RecordingCanvas::new( 640.0, 480.0 );
+	let ( mut stats, mut drawn ) = ( Vec::new(), Vec::new() );
+	for _ in 0..PASSES {
+		step( &mut sketch, &mut ctx, &mut canvas, false );
+		stats.push( sketch.hud( &ctx ) );
+		drawn.push( canvas.take().into_iter().filter( |command| !matches!( command, DrawCommand::Text { .. } ) ).collect() );
+	}
+	( stats, drawn )
+}
+
+/// Runs with whichever systems the build uses, so the serial ones without the parallel feature and the parallel ones with it
+#[test]
+fn bouncy_balls_repeat() {
+	let ( first_stats, first_drawn ) = bouncy_balls( 3 );
+	let ( second_stats, second_drawn ) = bouncy_balls( 3 );
+
+	for pass in 0..PASSES {
+		assert_eq!( first_stats[pass], second_stats[pass], "stats differ in pass {}", pass );
+		assert!( first_drawn[pass] == second_drawn[pass], "balls differ in pass {}", pass );
+	}
+	/* Something happened worth comparing */
+	assert!( first_stats.iter().any( |stats| stats[0] != "Collisions = 0" ) );
+	assert!( first_stats.iter().any( |stats| stats[1] != "Wall hits = 0" ) );
+}
+
+/// The parallel systems against the serial ones they replace, on copies of the same balls
+#[cfg(feature = "parallel")]
+mod parallel {
+	use miscmath::prelude::*;
+	use misc_ecs::prelude::*;
+	use rusty_nature_of_code::boundary::Boundary;
+	use rusty_nature_of_code::bouncy_balls::parallel::*;
+	use rusty_nature_of_code::bouncy_balls::prelude::*;
+	use rusty_nature_of_code::rng::SimRng;
+	use rusty_nature_of_code::snapshot::StoreSnapshot;
+	use rusty_nature_of_code::store::{Components, DenseStore};
+
+	const SCREEN_SIZE: ( i32, i32 ) = ( 640, 480 );
+	const COLUMNS: usize = 16;
+	const ROWS: usize = 12;
+
+	/// Balls with every component the systems use, every fourth one a box so the rigid body paths run too
+	#[derive(Clone)]
+	struct Balls {
+		regions: Regions,
+		acc_store: DenseStore<Vec2>,
+		vel_store: DenseStore<Vec2>,
+		pos_store: DenseStore<Vec2>,
+		atr_store: DenseStore<Attributes>,
+		mat_store: DenseStore<Material>,
+		shp_store: DenseStore<Shape>,
+	}
+
+	fn balls( count: usize ) -> Balls {
+		let mut rng = SimRng::new( 5 );
+		let mut entity_manager = EntityManager::new();
+		let mut balls = Balls { regions: regions( COLUMNS, ROWS ), acc_store: DenseStore::new(), vel_store: DenseStore::new(), pos_store: DenseStore::new(),
+								atr_store: DenseStore::new(), mat_store: DenseStore::new(), shp_store: DenseStore::new() };
+		for i in 0..count {
+			let entity = entity_manager.next();
+			let radius = rng.range( 5.0..15.0 );
+			balls.acc_store.add( entity, Vec2::default() );
+			balls.pos_store.add( entity, Vec2::new( &rng.range( radius..SCREEN_SIZE.0 as f32 - radius ), &rng.range( radius..SCREEN_SIZE.1 as f32 - radius ) ) );
+			balls.vel_store.add( entity, Vec2::new( &rng.range( -4.0..4.0 ), &rng.range( -4.0..4.0 ) ) );
+			balls.atr_store.add( entity, Attributes { mass: radius, color: Rgba::WHITE, radius, angle: 0.0, spin: 0.0, row: 0, column: 0 } );
+			balls.mat_store.add( entity, Material::default() );
+			if i % 4 == 0 {
+				balls.shp_store.add( entity, Shape::Rect { width: radius * 2.0, height: radius } );
+			}
+		}
+		balls
+	}
+
+	/// Everything a pass changes, sorted by entity so the order the dense stores were aligned in doesn't matter
+	#[derive(PartialEq)]
+	struct State {
+		pos: StoreSnapshot<[f32; 2]>,
+		vel: StoreSnapshot<[f32; 2]>,
+		atr: StoreSnapshot<Attributes>,
+		regions: Regions,
+	}
+
+	fn state( balls: &Balls ) -> State {
+		State { pos: StoreSnapshot::save( &balls.pos_store ), vel: StoreSnapshot::save( &balls.vel_store ), atr: StoreSnapshot::save( &balls.atr_store ),
+				regions: balls.regions.clone() }
+	}
+
+	#[test]
+	fn parallel_systems_match_serial() {
+		let mut serial = balls( 200 );
+		let mut parallel = serial.clone();
+		let boundary = Boundary::default();
+		let mut collisions = 0;
+
+		for pass in 0..100 {
+			for run in [&mut serial, &mut parallel] {
+				run.acc_store.for_each_mut( |_, acc| acc.y = -0.5 );
+			}
+
+			acceleration_system( &mut serial.acc_store, &mut serial.vel_store );
+			movement_system( SCREEN_SIZE, COLUMNS, ROWS, &mut serial.vel_store, &mut serial.pos_store, &mut serial.atr_store, &mut serial.regions );
+			let serial_collisions = collision_system( &mut serial.vel_store, &mut serial.pos_store, &mut serial.atr_store, &serial.mat_store, &serial.shp_store,
+													   &mut serial.regions );
+			let serial_crossings = boundary_system( SCREEN_SIZE, &boundary, &mut serial.vel_store, &mut serial.pos_store, &mut serial.atr_store,
+													&serial.mat_store, &serial.shp_store );
+
+			par_acceleration_system( &mut parallel.acc_store, &mut parallel.vel_store );
+			par_movement_system( SCREEN_SIZE, COLUMNS, ROWS, &mut parallel.vel_store, &mut parallel.pos_store, &mut parallel.atr_store, &mut parallel.regions );
+			let parallel_collisions = par_collision_system( &mut parallel.vel_store, &mut parallel.pos_store, &mut parallel.atr_store, &parallel.mat_store,
+															&parallel.shp_store, &parallel.regions );
+			let parallel_crossings = par_boundary_system( SCREEN_SIZE, &boundary, &mut parallel.vel_store, &mut parallel.pos_store, &mut parallel.atr_store,
+														  &parallel.mat_store, &parallel.shp_store );
+
+			assert_eq!( serial_collisions, parallel_collisions, "collisions differ in pass {}", pass );
+			assert_eq!( serial_crossings, parallel_crossings, "crossings differ in pass {}", pass );
+			assert!( state( &serial ) == state( &parallel ), "state differs after pass {}", pass );
+			collisions += serial_collisions.len();
+		}
+		assert!( collisions > 0 );
+	}
+}
@@ -0,0 +1,93 @@
+//! When events are sent and cleared, and how often the systems sending them do.
+
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use rusty_nature_of_code::boundary::Boundary;
+use rusty_nature_of_code::events::*;
+use rusty_nature_of_code::rng::SimRng;
+use rusty_nature_of_code::schedule::{Schedule, Stage, System};
+use rusty_nature_of_code::sketch::Context;
+use rusty_nature_of_code::smart_rockets::prelude::*;
+use rusty_nature_of_code::store::{Components, DenseStore};
+use rusty_nature_of_code::world::{self, Pos};
+
+#[test]
+fn input_events_last_the_pass() {
+	let mut world = world::World::new();
+	world.add_event::<WallHit>();
+	let entity = EntityManager::new().next();
+
+	let mut schedule = Schedule::new();
+	schedule
+		.at_start( clear_events )
+		/* Counts the events the input stage sent, which are still there in the forces stage */
+		.add( System::new( "count", Stage::Forces, |world: &mut world::World, _ctx| {
+			let hits = world.events::<WallHit>().len();
+			world.insert_resource( hits );
+		}))
+		.add( System::new( "key", Stage::Input, move |world: &mut world::World, _ctx| world.send( WallHit { entity, side: Side::Left } ) ) );
+	schedule.build().unwrap();
+
+	let mut ctx = Context::new( ( 640, 480 ), SimRng::new( 0 ) );
+	schedule.input( &mut world, &mut ctx );
+	schedule.update( &mut world, &mut ctx );
+	assert_eq!( *world.resource::<usize>(), 1 );
+	assert_eq!( world.events::<WallHit>().len(), 1 );
+
+	/* The next pass starts by clearing them, even without input */
+	ctx.frame += 1;
+	schedule.update( &mut world, &mut ctx );
+	assert_eq!( *world.resource::<usize>(), 0 );
+}
+
+#[test]
+fn spawning_sends_spawned() {
+	let mut world = world::World::new();
+	world.register::<Pos>().add_event::<Spawned>();
+
+	let first = world.spawn( ( Pos, Vec2::default() ) );
+	let second = world.spawn( ( Pos, Vec2::new( &1.0, &0.0 ) ) );
+	assert_eq!( world.events::<Spawned>().iter().map( |spawned| spawned.entity ).collect::<Vec<_>>(), [ first, second ] );
+	/* The components are in their stores by the time it's sent */
+	assert!( world.events::<Spawned>().iter().all( |spawned| world.store::<Pos>().get( spawned.entity ).is_some() ) );
+
+	/* A world which didn't add the event spawns without sending it */
+	let mut world = world::World::new();
+	world.register::<Pos>();
+	world.spawn( ( Pos, Vec2::default() ) );
+	assert_eq!( world.len(), 1 );
+}
+
+#[test]
+fn rockets_reach_the_target_once_a_generation() {
+	let world = World {
+		width: 640.0,
+		height: 480.0,
+		default_pos: Vec2::new( &320.0, &0.0 ),
+		default_vel: Vec2::default(),
+		target_pos: Vec2::new( &320.0, &480.0 ),
+		mutation_chance: 0.01,
+		lifespan: 10,
+		boundary: Boundary::default(),
+		gen_done: false,
+	};
+	let mut entity_manager = EntityManager::new();
+	let ( mut pos_store, mut vel_store, mut atr_store, mut gene_store ) = ( DenseStore::new(), DenseStore::new(), DenseStore::new(), DenseStore::new() );
+	for _ in 0..2 {
+		let entity = entity_manager.next();
+		pos_store.add( entity, world.target_pos );
+		vel_store.add( entity, Vec2::default() );
+		atr_store.add( entity, Attributes { radius: 10.0, color: Rgba::WHITE, reached: false } );
+		gene_store.add( entity, vec![ 0; world.lifespan ] );
+	}
+
+	/* Both rockets sit on the target for several passes, but only reach it on the first */
+	assert_eq!( target_system( &world, 0, &pos_store, &mut atr_store ).len(), 2 );
+	assert!( target_system( &world, 1, &pos_store, &mut atr_store ).is_empty() );
+	assert!( target_system( &world, 2, &pos_store, &mut atr_store ).is_empty() );
+
+	/* The next generation can reach it again */
+	genetic_system( &world, &mut SimRng::new( 0 ), &mut pos_store, &mut vel_store, &mut atr_store, &mut gene_store ).unwrap();
+	pos_store.for_each_mut( |_, pos| *pos = world.target_pos );
+	assert_eq!( target_system( &world, 10, &pos_store, &mut atr_store ).len(), 2 );
+}
@@ -0,0 +1,50 @@
+//! Headless smart rocket runs over arenas of different shapes.
+
+use std::fs;
+use rusty_nature_of_code::smart_rockets::headless::*;
+
+/// A tall, narrow arena starts every rocket further from the target than the arena is wide
+#[test]
+fn tall_narrow_arena_evolves() {
+	let config = RunConfig { width: 200.0, height: 800.0, generations: 5, ..Default::default() };
+	config.validate().unwrap();
+
+	let stats = run(&config).unwrap();
+	assert_eq!(stats.len(), 5);
+	assert!(stats.iter().all(|generation| generation.best_distance.is_finite() && generation.mean_distance.is_finite()));
+}
+
+#[test]
+fn tall_narrow_sweep_writes_every_config() {
+	let out = std::env::temp_dir().join(format!("noc_tall_sweep_{}", std::process::id()));
+	let sweep = Sweep { populations: vec![2, 10], seeds: vec![0, 1, 2], generations: 3, width: 200.0, height: 800.0, ..Default::default() };
+
+	let paths = sweep.run(&out, 2).unwrap();
+	assert_eq!(paths.len(), 2);
+	for path in &paths {
+		/* A header, then a row per seed and generation */
+		assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 1 + 3 * 3);
+	}
+	fs::remove_dir_all(&out).unwrap();
+}
+
+#[test]
+fn invalid_config_is_an_error() {
+	let config = RunConfig { population: 1, ..Default::default() };
+	assert_eq!(run(&config).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// Each configuration's csv is named after its values, so a repeated value would write the same file twice
+#[test]
+fn repeated_sweep_values_are_an_error() {
+	let out = std::env::temp_dir().join(format!("noc_repeated_sweep_{}", std::process::id()));
+	let sweep = Sweep { mutation_chances: vec![0.1, 0.1], populations: vec![5], lifespans: vec![20], seeds: vec![1], generations: 2, ..Default::default() };
+
+	let error = sweep.run(&out, 2).unwrap_err();
+	assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+	assert!(error.to_string().contains("mutation chance 0.1"));
+	assert!(!out.exists());
+
+	let sweep = Sweep { seeds: vec![1, 2, 1], ..Default::default() };
+	assert_eq!(sweep.validate().unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
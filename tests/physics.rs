@@ -0,0 +1,111 @@
+//! Bodies falling under gravity onto the floor and each other, stepped through the bouncy balls systems for many passes.
+
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use rusty_nature_of_code::boundary::Boundary;
+use rusty_nature_of_code::bouncy_balls::prelude::*;
+use rusty_nature_of_code::store::{Components, DenseStore};
+
+const SCREEN_SIZE: ( i32, i32 ) = ( 640, 480 );
+/* Collisions are only found between bodies in the same region, and rows and columns are clamped to at least 1, so two of
+   each puts every body in one region however far it slides */
+const COLUMNS: usize = 2;
+const ROWS: usize = 2;
+/// Acceleration added to each body every pass
+const GRAVITY: f32 = -0.5;
+
+/// Bodies with every component the systems use
+struct Bodies {
+	entity_manager: EntityManager,
+	regions: Regions,
+	acc_store: DenseStore<Vec2>,
+	vel_store: DenseStore<Vec2>,
+	pos_store: DenseStore<Vec2>,
+	atr_store: DenseStore<Attributes>,
+	mat_store: DenseStore<Material>,
+	shp_store: DenseStore<Shape>,
+}
+
+impl Bodies {
+	fn new() -> Bodies {
+		Bodies { entity_manager: EntityManager::new(), regions: regions( COLUMNS, ROWS ), acc_store: DenseStore::new(), vel_store: DenseStore::new(),
+				 pos_store: DenseStore::new(), atr_store: DenseStore::new(), mat_store: DenseStore::new(), shp_store: DenseStore::new() }
+	}
+
+	/// Adds a wooden body of shape at rest at pos turned by angle, with a radius reaching its corners
+	fn add( &mut self, shape: Shape, pos: ( f32, f32 ), angle: f32 ) -> Entity {
+		let entity = self.entity_manager.next();
+		let radius = shape.points( &Vec2::default(), 0.0 ).iter().map( |point| point.x.hypot( point.y ) ).fold( 0.0, f32::max );
+		self.acc_store.add( entity, Vec2::default() );
+		self.vel_store.add( entity, Vec2::default() );
+		self.pos_store.add( entity, Vec2::new( &pos.0, &pos.1 ) );
+		self.atr_store.add( entity, Attributes { mass: 1.0, color: Rgba::WHITE, radius, angle, spin: 0.0, row: 0, column: 0 } );
+		self.mat_store.add( entity, Material::WOOD );
+		self.shp_store.add( entity, shape );
+		entity
+	}
+
+	/// Runs the systems in the order the sketch does, for passes passes
+	fn step( &mut self, passes: usize ) {
+		for _ in 0..passes {
+			self.acc_store.for_each_mut( |_, acc| acc.y = GRAVITY );
+			acceleration_system( &mut self.acc_store, &mut self.vel_store );
+			movement_system( SCREEN_SIZE, COLUMNS, ROWS, &mut self.vel_store, &mut self.pos_store, &mut self.atr_store, &mut self.regions );
+			collision_system( &mut self.vel_store, &mut self.pos_store, &mut self.atr_store, &self.mat_store, &self.shp_store, &mut self.regions );
+			boundary_system( SCREEN_SIZE, &Boundary::default(), &mut self.vel_store, &mut self.pos_store, &mut self.atr_store, &self.mat_store,
+							 &self.shp_store );
+		}
+	}
+
+	/// How far the shapes of a and b overlap, 0 if they don't
+	fn overlap( &self, a: Entity, b: Entity ) -> f32 {
+		let ( atr_a, atr_b ) = ( self.atr_store.get( a ).unwrap(), self.atr_store.get( b ).unwrap() );
+		collide( self.shp_store.get( a ).unwrap(), self.pos_store.get( a ).unwrap(), atr_a.angle,
+				 self.shp_store.get( b ).unwrap(), self.pos_store.get( b ).unwrap(), atr_b.angle ).map_or( 0.0, |manifold| manifold.depth )
+	}
+}
+
+#[test]
+fn stacked_bodies_rest_on_each_other() {
+	let mut bodies = Bodies::new();
+	let bottom = bodies.add( Shape::Rect { width: 60.0, height: 20.0 }, ( 320.0, 10.0 ), 0.0 );
+	let middle = bodies.add( Shape::Rect { width: 40.0, height: 20.0 }, ( 320.0, 30.0 ), 0.0 );
+	let top = bodies.add( Shape::Polygon( vec![ Vec2::new( &-15.0, &-10.0 ), Vec2::new( &15.0, &-10.0 ), Vec2::new( &0.0, &10.0 ) ] ), ( 320.0, 50.0 ), 0.0 );
+
+	/* Gravity pulls each body into the one below every pass, which pushes it back out. The floor only pushes the bottom
+	   body back up after that, so the stack settles a little into itself, but then stays put instead of sinking through */
+	bodies.step( 200 );
+	let settled = [ bodies.overlap( bottom, middle ), bodies.overlap( middle, top ) ];
+	bodies.step( 100 );
+	for ( ( lower, upper ), settled ) in [ ( bottom, middle ), ( middle, top ) ].into_iter().zip( settled ) {
+		let overlap = bodies.overlap( lower, upper );
+		assert!( overlap < 5.0, "overlap of {}", overlap );
+		assert!( ( overlap - settled ).abs() < 0.01, "overlap went from {} to {}", settled, overlap );
+	}
+	let height = |entity| bodies.pos_store.get( entity ).unwrap().y;
+	assert!( ( height( bottom ) - 10.0 ).abs() < 0.01, "bottom at {}", height( bottom ) );
+	assert!( height( middle ) > 25.0, "middle at {}", height( middle ) );
+	assert!( height( top ) > 45.0, "top at {}", height( top ) );
+}
+
+#[test]
+fn boxes_dropped_on_a_corner_tip_over() {
+	let mut bodies = Bodies::new();
+	let below = bodies.add( Shape::Rect { width: 60.0, height: 20.0 }, ( 320.0, 10.0 ), 0.0 );
+	/* Turned an eighth of a turn and a little off centre, so it lands on a corner to the right of its middle */
+	let dropped = bodies.add( Shape::Rect { width: 20.0, height: 20.0 }, ( 326.0, 60.0 ), std::f32::consts::FRAC_PI_4 );
+
+	let mut spun = false;
+	for _ in 0..300 {
+		bodies.step( 1 );
+		spun |= bodies.atr_store.get( dropped ).unwrap().spin.abs() > 0.01;
+		let overlap = bodies.overlap( below, dropped );
+		assert!( overlap < 4.0, "overlap of {}", overlap );
+	}
+
+	/* The corner hit turns it over onto a side, where it stays resting on the box below */
+	assert!( spun );
+	let angle = bodies.atr_store.get( dropped ).unwrap().angle;
+	assert!( ( angle - std::f32::consts::FRAC_PI_2 ).abs() < 0.2, "angle of {}", angle );
+	assert!( bodies.pos_store.get( dropped ).unwrap().y > bodies.pos_store.get( below ).unwrap().y + 15.0 );
+}
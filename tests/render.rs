@@ -0,0 +1,106 @@
+//! The render systems drawn on a recording canvas, checking what they draw and where.
+
+use miscmath::prelude::*;
+use misc_ecs::prelude::*;
+use rusty_nature_of_code::boundary::Boundary;
+use rusty_nature_of_code::bouncy_balls::prelude::*;
+use rusty_nature_of_code::canvas::{DrawCommand, RecordingCanvas};
+use rusty_nature_of_code::smart_rockets::prelude as rockets;
+use rusty_nature_of_code::store::{Components, DenseStore};
+
+/// Height of the canvas, which positions are flipped over so that y = 0 is the bottom of the screen
+const HEIGHT: f32 = 480.0;
+
+fn ball( pos: ( f32, f32 ), radius: f32, color: Rgba ) -> ( Vec2, Attributes ) {
+	( Vec2::new( &pos.0, &pos.1 ), Attributes { mass: 1.0, color, radius, angle: 0.0, spin: 0.0, row: 0, column: 0 } )
+}
+
+#[test]
+fn balls_are_drawn_in_their_shapes() {
+	let mut entity_manager = EntityManager::new();
+	let ( mut pos_store, mut atr_store, mut shp_store ) = ( DenseStore::new(), DenseStore::new(), DenseStore::new() );
+	for ( ( pos, atr ), shape ) in [
+		( ball( ( 100.0, 100.0 ), 10.0, Rgba::RED ), None ),
+		( ball( ( 200.0, 50.0 ), 10.0, Rgba::WHITE ), Some( Shape::Rect { width: 20.0, height: 10.0 } ) ),
+		( ball( ( 300.0, 200.0 ), 8.0, Rgba::BLACK ), Some( Shape::Circle { radius: 8.0 } ) ),
+	] {
+		let entity = entity_manager.next();
+		pos_store.add( entity, pos );
+		atr_store.add( entity, atr );
+		if let Some( shape ) = shape {
+			shp_store.add( entity, shape );
+		}
+	}
+
+	let mut canvas = RecordingCanvas::new( 640.0, HEIGHT );
+	render_system( &mut canvas, &pos_store, &atr_store, &shp_store );
+
+	assert_eq!( canvas.commands(), &[
+		/* A ball without a shape is a plain circle */
+		DrawCommand::Circle { center: Vec2::new( &100.0, &( HEIGHT - 100.0 ) ), radius: 10.0, color: Rgba::RED },
+		/* A box is drawn from its corners, anticlockwise before they are flipped */
+		DrawCommand::Polygon { points: vec![
+			Vec2::new( &190.0, &( HEIGHT - 45.0 ) ),
+			Vec2::new( &210.0, &( HEIGHT - 45.0 ) ),
+			Vec2::new( &210.0, &( HEIGHT - 55.0 ) ),
+			Vec2::new( &190.0, &( HEIGHT - 55.0 ) ),
+		], color: Rgba::WHITE },
+		/* A circle shape has a line out to its edge at its angle */
+		DrawCommand::Circle { center: Vec2::new( &300.0, &( HEIGHT - 200.0 ) ), radius: 8.0, color: Rgba::BLACK },
+		DrawCommand::Line { from: Vec2::new( &300.0, &( HEIGHT - 200.0 ) ), to: Vec2::new( &308.0, &( HEIGHT - 200.0 ) ), thickness: 2.0,
+							color: Rgba::new( 0, 0, 0, 120 ) },
+	] );
+}
+
+#[test]
+fn obstacles_are_drawn_in_their_shapes() {
+	let obstacle = |collider| Obstacle { collider, material: Material::default(), color: Rgba::BLACK };
+	let obstacles = [
+		obstacle( Collider::Circle { pos: Vec2::new( &320.0, &240.0 ), radius: 30.0 } ),
+		obstacle( Collider::Segment { from: Vec2::new( &0.0, &100.0 ), to: Vec2::new( &200.0, &150.0 ) } ),
+		obstacle( Collider::Aabb { min: Vec2::new( &400.0, &0.0 ), max: Vec2::new( &500.0, &40.0 ) } ),
+	];
+
+	let mut canvas = RecordingCanvas::new( 640.0, HEIGHT );
+	obstacle_render_system( &mut canvas, &obstacles );
+
+	assert_eq!( canvas.commands(), &[
+		DrawCommand::Circle { center: Vec2::new( &320.0, &( HEIGHT - 240.0 ) ), radius: 30.0, color: Rgba::BLACK },
+		DrawCommand::Line { from: Vec2::new( &0.0, &( HEIGHT - 100.0 ) ), to: Vec2::new( &200.0, &( HEIGHT - 150.0 ) ), thickness: 3.0, color: Rgba::BLACK },
+		DrawCommand::Polygon { points: vec![
+			Vec2::new( &400.0, &HEIGHT ),
+			Vec2::new( &500.0, &HEIGHT ),
+			Vec2::new( &500.0, &( HEIGHT - 40.0 ) ),
+			Vec2::new( &400.0, &( HEIGHT - 40.0 ) ),
+		], color: Rgba::BLACK },
+	] );
+}
+
+#[test]
+fn rockets_are_drawn_with_the_target() {
+	let world = rockets::World {
+		width: 640.0,
+		height: HEIGHT,
+		default_pos: Vec2::new( &320.0, &0.0 ),
+		default_vel: Vec2::default(),
+		target_pos: Vec2::new( &320.0, &HEIGHT ),
+		mutation_chance: 0.01,
+		lifespan: 100,
+		boundary: Boundary::default(),
+		gen_done: false,
+	};
+	let mut entity_manager = EntityManager::new();
+	let ( mut pos_store, mut atr_store ) = ( DenseStore::new(), DenseStore::new() );
+	let entity = entity_manager.next();
+	pos_store.add( entity, Vec2::new( &320.0, &60.0 ) );
+	atr_store.add( entity, rockets::Attributes { radius: 10.0, color: Rgba::WHITE, reached: false } );
+
+	let mut canvas = RecordingCanvas::new( 640.0, HEIGHT );
+	rockets::render_system( &mut canvas, &world, &pos_store, &atr_store );
+
+	assert_eq!( canvas.commands(), &[
+		DrawCommand::Circle { center: Vec2::new( &320.0, &( HEIGHT - 60.0 ) ), radius: 10.0, color: Rgba::WHITE },
+		/* The target is drawn over the rockets, at the top of the arena */
+		DrawCommand::Circle { center: Vec2::new( &320.0, &0.0 ), radius: rockets::TARGET_RADIUS, color: Rgba::RED },
+	] );
+}